@@ -2,22 +2,23 @@ use sarge::prelude::*;
 
 sarge! {
     /// This is a basic macros example.
+    #[sarge(auto_help = true, auto_version = true, version = env!("CARGO_PKG_VERSION"))]
     Args,
 
-    /// Show this help message.
-    'h' help: bool,
-
     /// The name to greet.
     'n' @NAME name: String,
 
     /// The number of times to greet.
-    /// Defaults to 1.
-    #ok times: u32,
+    #ok times: u32 = 1,
 }
 
 fn main() {
     let args = match Args::parse() {
         Ok((a, _)) => a,
+        Err(ArgParseError::HelpRequested(text) | ArgParseError::VersionRequested(text)) => {
+            print!("{text}");
+            return;
+        }
         Err(e) => {
             eprintln!("failed to parse arguments: {e}");
             Args::print_help();
@@ -25,11 +26,6 @@ fn main() {
         }
     };
 
-    if args.help {
-        Args::print_help();
-        return;
-    }
-
     for _ in 0..args.times.unwrap_or(1) {
         println!("Hello, {}!", args.name);
     }
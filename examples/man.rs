@@ -0,0 +1,14 @@
+use sarge::prelude::*;
+
+// View with `cargo run --example man | man -l -`
+fn main() {
+    let mut parser = ArgumentReader::new();
+    parser.doc = Some("An example demonstrating man page generation.\n\nThe first line of the documentation becomes the summary in NAME; all of\nit goes in DESCRIPTION.".into());
+    parser.add::<bool>(tag::both('a', "abc").env("ABC").doc("Super duper docs"));
+    parser.add::<bool>(tag::short('b').env("BAR"));
+    parser.add::<String>(tag::long("baz-arg"));
+    parser.add::<u32>(tag::both('f', "foo").doc("Hello, World!"));
+    parser.add::<Vec<i8>>(tag::env("ENV_ONLY").doc("Only settable from the environment"));
+
+    print!("{}", parser.manpage());
+}
@@ -0,0 +1,430 @@
+//! Shell completion script generation.
+
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::tag::{Cli, Full};
+use crate::{ArgResult, ArgumentType};
+
+/// A shell to generate a completion script for. See
+/// [`ArgumentReader::completions`](crate::ArgumentReader::completions).
+///
+/// Only available on feature `completions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Shell {
+    /// GNU Bash. Source the script, or install it to
+    /// `/usr/share/bash-completion/completions/<name>`.
+    Bash,
+    /// Z shell. Install the script as `_<name>` somewhere on your `$fpath`.
+    Zsh,
+    /// The friendly interactive shell. Install the script to
+    /// `~/.config/fish/completions/<name>.fish`.
+    Fish,
+}
+
+/// An error from parsing a [`Shell`]. Holds the unrecognized name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownShell(pub String);
+
+impl Display for UnknownShell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unknown shell: `{}` (expected one of bash, zsh, fish)",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownShell {}
+
+impl FromStr for Shell {
+    type Err = UnknownShell;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            _ => Err(UnknownShell(s.to_string())),
+        }
+    }
+}
+
+impl Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        })
+    }
+}
+
+impl ArgumentType for Shell {
+    type Error = UnknownShell;
+
+    const VALUE_NAME: &'static str = "SHELL";
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        val.map(str::parse)
+    }
+}
+
+/// Supplies candidate values for an argument during dynamic completion.
+/// Given the partial value under the cursor, returns every value that might
+/// complete it. See [`Full::completer`].
+///
+/// Only available on feature `completions`.
+pub type Completer = Arc<dyn Fn(&str) -> Vec<String> + Send + Sync>;
+
+/// Wraps a [`Completer`] so [`Full`] can stay `Debug`.
+#[derive(Clone)]
+pub(crate) struct CompleterFn(pub Completer);
+
+impl fmt::Debug for CompleterFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Completer")
+    }
+}
+
+/// The environment variable which triggers dynamic completion. See
+/// [`ArgumentReader::complete`](crate::ArgumentReader::complete).
+pub(crate) const COMPLETE_VAR: &str = "SARGE_COMPLETE";
+
+/// Everything a completion script needs to know about an argument.
+pub(crate) struct Completion<'a> {
+    pub short: Option<char>,
+    pub long: Option<&'a str>,
+    pub doc: Option<&'a str>,
+    pub takes_value: bool,
+    pub repeatable: bool,
+    pub completer: Option<&'a Completer>,
+}
+
+impl<'a> Completion<'a> {
+    /// Returns `None` for arguments without a CLI component.
+    pub fn new(tag: &'a Full, takes_value: bool, repeatable: bool) -> Option<Self> {
+        let (short, long) = match tag.cli.as_ref()? {
+            Cli::Short(s) => (Some(*s), None),
+            Cli::Long(l) => (None, Some(l.as_str())),
+            Cli::Both(s, l) => (Some(*s), Some(l.as_str())),
+        };
+
+        Some(Self {
+            short,
+            long,
+            doc: tag.doc.as_deref().and_then(|doc| doc.lines().next()),
+            takes_value,
+            repeatable,
+            completer: tag.completer.as_ref().map(|completer| &completer.0),
+        })
+    }
+
+    /// Every form of this flag, e.g. `["-f", "--foo"]`.
+    fn flags(&self) -> Vec<String> {
+        let short = self.short.map(|s| format!("-{s}"));
+        let long = self.long.map(|l| format!("--{l}"));
+        short.into_iter().chain(long).collect()
+    }
+}
+
+/// Renders a completion script for `name` in the given shell.
+pub(crate) fn render(shell: Shell, name: &str, args: &[Completion]) -> String {
+    match shell {
+        Shell::Bash => bash(name, args),
+        Shell::Zsh => zsh(name, args),
+        Shell::Fish => fish(name, args),
+    }
+}
+
+/// Replaces anything that can't go in a shell function name with `_`.
+fn function_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect()
+}
+
+fn bash(name: &str, args: &[Completion]) -> String {
+    let func = function_name(name);
+    let all: Vec<_> = args.iter().flat_map(Completion::flags).collect();
+    let valued: Vec<_> = args
+        .iter()
+        .filter(|arg| arg.takes_value)
+        .flat_map(Completion::flags)
+        .collect();
+
+    let mut out = String::new();
+    let _ = writeln!(out, "_{func}() {{");
+    out.push_str("    local cur prev\n");
+    out.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    out.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n\n");
+
+    if !valued.is_empty() {
+        out.push_str("    case \"$prev\" in\n");
+        let _ = writeln!(out, "        {})", valued.join("|"));
+        out.push_str("            COMPREPLY=($(compgen -f -- \"$cur\"))\n");
+        out.push_str("            return 0\n");
+        out.push_str("            ;;\n");
+        out.push_str("    esac\n\n");
+    }
+
+    out.push_str("    if [[ \"$cur\" == -* ]]; then\n");
+    let _ = writeln!(
+        out,
+        "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        all.join(" ")
+    );
+    out.push_str("        return 0\n");
+    out.push_str("    fi\n\n");
+    out.push_str("    COMPREPLY=($(compgen -f -- \"$cur\"))\n");
+    out.push_str("}\n\n");
+    let _ = writeln!(out, "complete -F _{func} {name}");
+
+    out
+}
+
+/// Escapes a description for use inside `'...[description]...'`.
+fn zsh_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh(name: &str, args: &[Completion]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "#compdef {name}\n");
+    out.push_str("_arguments -s \\\n");
+
+    for arg in args {
+        let flags = arg.flags();
+        let doc = arg.doc.map(zsh_escape).unwrap_or_default();
+        let value = if arg.takes_value { ":value:_files" } else { "" };
+
+        let (exclusive, repeat) = if arg.repeatable {
+            (String::new(), "*")
+        } else if flags.len() > 1 {
+            (format!("({})", flags.join(" ")), "")
+        } else {
+            (String::new(), "")
+        };
+
+        if let [flag] = &flags[..] {
+            let _ = writeln!(out, "    '{repeat}{flag}[{doc}]{value}' \\");
+        } else {
+            let _ = writeln!(
+                out,
+                "    '{exclusive}{repeat}'{{{}}}'[{doc}]{value}' \\",
+                flags.join(",")
+            );
+        }
+    }
+
+    out.push_str("    '*:file:_files'\n");
+    out
+}
+
+/// Escapes a string for use inside fish's single quotes.
+fn fish_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish(name: &str, args: &[Completion]) -> String {
+    let mut out = String::new();
+
+    for arg in args {
+        let _ = write!(out, "complete -c {name}");
+        if let Some(short) = arg.short {
+            let _ = write!(out, " -s {short}");
+        }
+
+        if let Some(long) = arg.long {
+            let _ = write!(out, " -l {long}");
+        }
+
+        if arg.takes_value {
+            out.push_str(" -r");
+        }
+
+        if let Some(doc) = arg.doc {
+            let _ = write!(out, " -d '{}'", fish_escape(doc));
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Renders a script for `name` which asks the program itself for candidates,
+/// by running it with [`COMPLETE_VAR`] set to the shell's name and the words
+/// up to (and including) the one under the cursor.
+pub(crate) fn register(shell: Shell, name: &str) -> String {
+    let func = function_name(name);
+    let mut out = String::new();
+
+    match shell {
+        Shell::Bash => {
+            let _ = writeln!(out, "_{func}() {{");
+            out.push_str("    local IFS=$'\\n'\n");
+            let _ = writeln!(
+                out,
+                "    COMPREPLY=($({COMPLETE_VAR}=bash \"${{COMP_WORDS[0]}}\" \"${{COMP_WORDS[@]:1:COMP_CWORD}}\"))"
+            );
+            out.push_str("}\n\n");
+            let _ = writeln!(out, "complete -o default -F _{func} {name}");
+        }
+        Shell::Zsh => {
+            let _ = writeln!(out, "#compdef {name}\n");
+            let _ = writeln!(out, "_{func}() {{");
+            out.push_str("    local -a candidates\n");
+            let _ = writeln!(
+                out,
+                "    candidates=(\"${{(@f)$({COMPLETE_VAR}=zsh \"${{words[1]}}\" \"${{(@)words[2,CURRENT]}}\")}}\")"
+            );
+            out.push_str("    if [[ -n \"${candidates[*]}\" ]]; then\n");
+            out.push_str("        _describe 'values' candidates\n");
+            out.push_str("    else\n");
+            out.push_str("        _files\n");
+            out.push_str("    fi\n");
+            out.push_str("}\n\n");
+            let _ = writeln!(out, "compdef _{func} {name}");
+        }
+        Shell::Fish => {
+            let _ = writeln!(
+                out,
+                "complete -c {name} -a '(env {COMPLETE_VAR}=fish {name} (commandline -opc)[2..-1] (commandline -ct | string collect -a))'"
+            );
+        }
+    }
+
+    out
+}
+
+/// Joins `--name`, `=` and `value` back together, since bash splits words
+/// on `=`.
+fn join_bash_words(words: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(words.len());
+    let mut joined = false;
+
+    for word in words {
+        match out.last_mut() {
+            Some(last) if word == "=" && last.starts_with("--") && !last.contains('=') => {
+                last.push('=');
+                joined = true;
+            }
+            Some(last) if joined => {
+                last.push_str(&word);
+                joined = false;
+            }
+            _ => out.push(word),
+        }
+    }
+
+    out
+}
+
+/// Finds the argument expecting a value after `word`, if any.
+fn awaiting_value<'a>(word: &str, args: &'a [Completion<'a>]) -> Option<&'a Completion<'a>> {
+    if let Some(long) = word.strip_prefix("--") {
+        if long.contains('=') {
+            return None;
+        }
+
+        args.iter()
+            .find(|arg| arg.takes_value && arg.long == Some(long))
+    } else if let Some(shorts) = word.strip_prefix('-') {
+        shorts.chars().find_map(|short| {
+            args.iter()
+                .find(|arg| arg.takes_value && arg.short == Some(short))
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the values for `arg` which start with `prefix`.
+fn values(arg: &Completion, prefix: &str) -> Vec<String> {
+    arg.completer
+        .map(|completer| completer(prefix))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|val| val.starts_with(prefix))
+        .collect()
+}
+
+/// Renders the candidates for the last of `words` (which excludes the
+/// executable), one per line.
+pub(crate) fn candidates(shell: Shell, words: Vec<String>, args: &[Completion]) -> String {
+    let words = if shell == Shell::Bash {
+        join_bash_words(words)
+    } else {
+        words
+    };
+
+    let Some((current, previous)) = words.split_last() else {
+        return String::new();
+    };
+
+    if previous.iter().any(|word| word == "--") {
+        return String::new();
+    }
+
+    // (candidate, description)
+    let mut found: Vec<(String, Option<&str>)> = Vec::new();
+
+    if let Some(arg) = previous.last().and_then(|prev| awaiting_value(prev, args)) {
+        found.extend(values(arg, current).into_iter().map(|val| (val, None)));
+    } else if let Some((long, prefix)) = current
+        .strip_prefix("--")
+        .and_then(|rest| rest.split_once('='))
+    {
+        if let Some(arg) = args
+            .iter()
+            .find(|arg| arg.takes_value && arg.long == Some(long))
+        {
+            for val in values(arg, prefix) {
+                // bash only replaces the part after the `=`
+                let val = if shell == Shell::Bash {
+                    val
+                } else {
+                    format!("--{long}={val}")
+                };
+                found.push((val, None));
+            }
+        }
+    } else if current.starts_with('-') {
+        for arg in args {
+            for flag in arg.flags() {
+                if flag.starts_with(current.as_str()) {
+                    found.push((flag, arg.doc));
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (val, doc) in found {
+        match (shell, doc) {
+            (Shell::Zsh, Some(doc)) => {
+                let _ = writeln!(out, "{}:{doc}", val.replace(':', "\\:"));
+            }
+            (Shell::Zsh, None) => {
+                let _ = writeln!(out, "{}", val.replace(':', "\\:"));
+            }
+            (Shell::Fish, Some(doc)) => {
+                let _ = writeln!(out, "{val}\t{doc}");
+            }
+            _ => {
+                let _ = writeln!(out, "{val}");
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,185 @@
+//! A tiny parser for INI/TOML-lite configuration files.
+//!
+//! ```plain
+//! # Comments start with `#` or `;`.
+//! name = "quoted \"value\""
+//! count = 3
+//! tags = [a, "b c", 'd']
+//!
+//! [net]
+//! port = 8080 # becomes the key `net.port`
+//! ```
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::ArgParseError;
+
+/// A single `key = value` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Entry {
+    /// The 1-based line number.
+    pub line: usize,
+    pub key: String,
+    pub values: Vec<String>,
+    /// Whether the value was an array, whose items shouldn't be split again.
+    pub array: bool,
+}
+
+/// Parses a whole configuration file.
+pub(crate) fn parse(src: &str) -> Result<Vec<Entry>, ArgParseError> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+
+    for (i, line) in src.lines().enumerate() {
+        let line_no = i + 1;
+        let err = |msg: &str| ArgParseError::InvalidConfig(line_no, msg.to_string());
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let (name, rest) = header
+                .split_once(']')
+                .ok_or_else(|| err("unterminated section header"))?;
+            if !is_comment_or_empty(rest) {
+                return Err(err("unexpected text after section header"));
+            }
+
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| err("expected `key = value`"))?;
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(err("missing key"));
+        }
+
+        let key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{section}.{key}")
+        };
+
+        let value = value.trim();
+        let values = parse_value(value).map_err(|msg| err(&msg))?;
+        entries.push(Entry {
+            line: line_no,
+            key,
+            values,
+            array: value.starts_with('['),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn is_comment_or_empty(s: &str) -> bool {
+    let s = s.trim();
+    s.is_empty() || s.starts_with(['#', ';'])
+}
+
+/// Parses the right-hand side of a `key = value` line.
+fn parse_value(src: &str) -> Result<Vec<String>, String> {
+    let mut chars = src.chars().peekable();
+
+    let values = if chars.peek() == Some(&'[') {
+        chars.next();
+        let mut values = Vec::new();
+
+        loop {
+            skip_whitespace(&mut chars);
+            match chars.peek() {
+                Some(']') => {
+                    chars.next();
+                    break;
+                }
+                None => return Err("unterminated array".to_string()),
+                _ => {}
+            }
+
+            values.push(parse_item(&mut chars, &[',', ']'])?);
+
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some(',') => {}
+                Some(']') => break,
+                _ => return Err("expected `,` or `]` in array".to_string()),
+            }
+        }
+
+        values
+    } else {
+        vec![parse_item(&mut chars, &[])?]
+    };
+
+    let rest: String = chars.collect();
+    if is_comment_or_empty(&rest) {
+        Ok(values)
+    } else {
+        Err(format!("unexpected text after value: `{}`", rest.trim()))
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+}
+
+/// Parses a single quoted or bare value. Bare values end at any of `ends`,
+/// or at a comment preceded by whitespace.
+fn parse_item(chars: &mut Peekable<Chars>, ends: &[char]) -> Result<String, String> {
+    let mut out = String::new();
+
+    match chars.peek() {
+        Some('"') => {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => out.push(match chars.next() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some(ch @ ('"' | '\\')) => ch,
+                        Some(ch) => return Err(format!("unknown escape `\\{ch}`")),
+                        None => return Err("unterminated string".to_string()),
+                    }),
+                    Some(ch) => out.push(ch),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+        }
+        Some('\'') => {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(ch) => out.push(ch),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+        }
+        _ => {
+            let mut prev_space = false;
+            while let Some(&ch) = chars.peek() {
+                if ends.contains(&ch) || (prev_space && matches!(ch, '#' | ';')) {
+                    break;
+                }
+
+                prev_space = ch.is_whitespace();
+                out.push(ch);
+                chars.next();
+            }
+
+            out.truncate(out.trim_end().len());
+        }
+    }
+
+    Ok(out)
+}
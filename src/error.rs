@@ -1,4 +1,6 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, io, path::PathBuf};
+
+use crate::Theme;
 
 /// An error that occurred while parsing arguments,
 /// either CLI, environment variables, or provided.
@@ -13,6 +15,30 @@ pub enum ArgParseError {
     /// Multiple short flags in a cluster (e.g. `-abc`) tried to consume the
     /// same value (e.g. `-abc only_one_value`).
     ConsumedValue(String),
+    /// A value wasn't valid UTF-8, but its argument type requires it. Holds
+    /// a lossy conversion of the value.
+    InvalidUnicode(String),
+    /// A configuration file was malformed. Holds the 1-based line number and
+    /// a description of the problem.
+    InvalidConfig(usize, String),
+    /// A file couldn't be read.
+    Io(PathBuf, io::ErrorKind),
+    /// A quote was never closed. Holds the file it was in (if any), and the
+    /// 1-based line and column of the opening quote.
+    UnterminatedQuote(Option<PathBuf>, usize, usize),
+    /// Argument files (`@path`) were nested too deeply, likely because one
+    /// includes itself. Holds the file that would've exceeded the limit.
+    ArgFileDepth(PathBuf),
+    /// The built-in help flag was given; see
+    /// [`ArgumentReader::auto_help`](crate::ArgumentReader::auto_help). Not
+    /// really an error: holds the rendered help, which should be printed to
+    /// stdout before exiting successfully.
+    HelpRequested(String),
+    /// The built-in version flag was given; see
+    /// [`ArgumentReader::auto_version`](crate::ArgumentReader::auto_version).
+    /// Like [`HelpRequested`](ArgParseError::HelpRequested), holds the text
+    /// to print.
+    VersionRequested(String),
 }
 
 impl Display for ArgParseError {
@@ -24,7 +50,64 @@ impl Display for ArgParseError {
                 f,
                 "Multiple arguments in `{s}` tried to consume the same value"
             ),
+            Self::InvalidUnicode(s) => write!(f, "Invalid UTF-8 in `{s}`"),
+            Self::InvalidConfig(line, s) => write!(f, "Invalid config on line {line}: {s}"),
+            Self::Io(path, kind) => write!(f, "Failed to read `{}`: {kind}", path.display()),
+            Self::UnterminatedQuote(Some(path), line, column) => write!(
+                f,
+                "Unterminated quote in `{}` at line {line}, column {column}",
+                path.display()
+            ),
+            Self::UnterminatedQuote(None, line, column) => {
+                write!(f, "Unterminated quote at line {line}, column {column}")
+            }
+            Self::ArgFileDepth(path) => write!(
+                f,
+                "Argument files are nested too deeply at `{}`",
+                path.display()
+            ),
+            Self::HelpRequested(s) | Self::VersionRequested(s) => f.write_str(s),
+        }
+    }
+}
+
+impl ArgParseError {
+    /// Returns the message, styled with ANSI escape codes from `theme`: the
+    /// names in backticks use [`Theme::flag`], and the whole message uses
+    /// [`Theme::error`]. Help and version text is returned as-is.
+    ///
+    /// Use [`ColorChoice::should_color`](crate::ColorChoice::should_color)
+    /// to decide whether to style at all, e.g.:
+    ///
+    /// ```
+    /// use sarge::{ArgParseError, ColorChoice, Theme};
+    ///
+    /// let err = ArgParseError::UnknownFlag("--foo".to_string());
+    /// let theme = if ColorChoice::Auto.should_color(&std::io::stderr()) {
+    ///     Theme::default()
+    /// } else {
+    ///     Theme::plain()
+    /// };
+    /// eprintln!("{}", err.styled(&theme));
+    /// ```
+    pub fn styled(&self, theme: &Theme) -> String {
+        let message = self.to_string();
+        if matches!(self, Self::HelpRequested(_) | Self::VersionRequested(_)) {
+            return message;
         }
+
+        let mut out = String::new();
+        for (i, part) in message.split('`').enumerate() {
+            if i % 2 == 1 {
+                out.push('`');
+                out.push_str(&Theme::paint(theme.flag, part));
+                out.push('`');
+            } else {
+                out.push_str(&Theme::paint(theme.error, part));
+            }
+        }
+
+        out
     }
 }
 
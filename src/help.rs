@@ -1,172 +1,538 @@
-use std::num::NonZeroUsize;
+use std::fmt::Write;
 
 use crate::tag::{Cli, Full};
+use crate::Theme;
 
-#[derive(Default, Debug, Clone, Copy)]
+/// The width used when neither an explicit width nor `COLUMNS` is given.
+const DEFAULT_WIDTH: usize = 80;
+
+/// The narrowest docs may get before they're moved below their argument.
+const MIN_DOC_WIDTH: usize = 24;
+
+/// How far docs are indented when moved below their argument.
+const DOC_INDENT: usize = 8;
+
+/// Everything about the program shown in help messages, besides the
+/// arguments.
+pub(crate) struct Page<'a> {
+    pub(crate) name: String,
+    pub(crate) version: Option<&'a str>,
+    pub(crate) usage: Option<&'a str>,
+    pub(crate) doc: Option<&'a str>,
+    pub(crate) examples: &'a [String],
+    pub(crate) see_also: &'a [String],
+}
+
+/// The usage synopsis when none is given.
+const DEFAULT_USAGE: &str = "[options...] <arguments...>";
+
+/// The widths of the columns shared by every argument in the help message.
+#[derive(Debug, Clone, Copy)]
 pub(crate) struct DocParams {
-    pub(crate) max_doc_width: usize,
+    pub(crate) theme: Theme,
+    pub(crate) width: usize,
     pub(crate) has_short: bool,
-    pub(crate) long_width: Option<NonZeroUsize>,
-    pub(crate) env_width: Option<NonZeroUsize>,
+    pub(crate) long_width: Option<usize>,
+    pub(crate) env_width: Option<usize>,
 }
 
-/// If `width.is_none()`, returns a single space. Else, returns width + 2 spaces.
-fn empty(width: Option<NonZeroUsize>) -> String {
-    " ".repeat(width.map_or(0, |x| usize::from(x) + 1) + 1)
+/// Returns the width of the help message: `explicit` if given, else the
+/// `COLUMNS` environment variable, else 80.
+pub(crate) fn terminal_width(explicit: Option<usize>) -> usize {
+    explicit
+        .or_else(|| std::env::var("COLUMNS").ok()?.trim().parse().ok())
+        .filter(|&width| width > 0)
+        .unwrap_or(DEFAULT_WIDTH)
 }
 
-/// Returns the padding necessary for continuing doc lines.
-fn doc_newline(params: DocParams) -> String {
-    let mut width = if params.has_short { 8 } else { 0 };
-
-    if let Some(long_width) = params.long_width {
-        width += usize::from(long_width) + 1;
+/// Returns how many columns `ch` takes up in a terminal: 0 for control and
+/// combining characters, 2 for wide (mostly East Asian and emoji)
+/// characters, and 1 otherwise. Symbols in U+2600..=U+27BF are only wide if
+/// they default to emoji presentation, like `⚡` (but not `☀`); a following
+/// U+FE0F variation selector isn't taken into account.
+fn char_width(ch: char) -> usize {
+    match u32::from(ch) {
+        0x00..=0x1F
+        | 0x7F..=0x9F
+        | 0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0E31
+        | 0x0E34..=0x0E3A
+        | 0x0E47..=0x0E4E
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0xE0100..=0xE01EF => 0,
+        0x1100..=0x115F
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x267F
+        | 0x2693
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26CE
+        | 0x26D4
+        | 0x26EA
+        | 0x26F2..=0x26F3
+        | 0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x274E
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
     }
+}
 
-    if let Some(env_width) = params.env_width {
-        width += usize::from(env_width) + 1;
+/// Returns how many columns `s` takes up in a terminal.
+pub(crate) fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/// Pushes `text` in the given style, then pads it with spaces to be `width`
+/// columns wide.
+fn pad(s: &mut String, text: &str, style: &str, width: usize) {
+    s.push_str(&Theme::paint(style, text));
+    s.push_str(&" ".repeat(width.saturating_sub(display_width(text))));
+}
+
+/// Returns the long-form flag and value placeholder for an argument, e.g.
+/// `--foo` and `<N>`.
+fn long_parts(arg: &Full, value: Option<&str>) -> (Option<String>, Option<String>) {
+    let placeholder = value.map(|value| format!("<{value}>"));
+
+    match &arg.cli {
+        Some(Cli::Long(long) | Cli::Both(_, long)) => (Some(format!("--{long}")), placeholder),
+        Some(Cli::Short(_)) => (None, placeholder),
+        None => (None, None),
     }
+}
 
-    " ".repeat(width)
+/// Returns the long-form column for an argument, including the value
+/// placeholder, e.g. `--foo <N>`. Short-only arguments just get the
+/// placeholder.
+fn long_column(arg: &Full, value: Option<&str>) -> Option<String> {
+    match long_parts(arg, value) {
+        (Some(long), Some(placeholder)) => Some(format!("{long} {placeholder}")),
+        (long, placeholder) => long.or(placeholder),
+    }
 }
 
-fn wrap_doc(doc: &str, params: DocParams) -> String {
-    assert!(
-        params.max_doc_width > 5,
-        "{} is not wide enough for docs",
-        params.max_doc_width
-    );
+/// Wraps `doc` at word boundaries to fit within `width` columns, indenting
+/// continuing lines by `indent` spaces. Words wider than `width` are split.
+/// Explicit newlines are kept.
+pub(crate) fn wrap(doc: &str, width: usize, indent: usize) -> String {
+    let width = width.max(1);
+    let mut lines = Vec::new();
 
-    if doc.len() < params.max_doc_width - 1 {
-        format!(" : {doc}")
-    } else {
-        let mut s = String::from(" : ");
-        let padding = doc_newline(params);
-
-        // TODO: add soft wrapping
-        let mut width = 2;
-        for ch in doc.chars() {
-            if width >= params.max_doc_width {
-                s.push_str("\n ");
-                s.push_str(&padding);
-                s.push(ch);
-                width = 1;
-            } else if ch == '\n' {
-                s.push_str("\n ");
-                s.push_str(&padding);
-                width = 0;
-            } else if ch != '\r' {
-                s.push(ch);
-                width += 1;
+    for paragraph in doc.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in paragraph.split_whitespace() {
+            let mut word = word;
+            let mut word_width = display_width(word);
+
+            if line_width > 0 && line_width + 1 + word_width <= width {
+                line.push(' ');
+                line.push_str(word);
+                line_width += 1 + word_width;
+                continue;
+            }
+
+            if line_width > 0 {
+                lines.push(std::mem::take(&mut line));
             }
+
+            // split words that don't fit on a line of their own
+            while word_width > width {
+                let mut taken = 0;
+                let split = word
+                    .char_indices()
+                    .find(|&(_, ch)| {
+                        taken += char_width(ch);
+                        taken > width
+                    })
+                    .map_or(word.len(), |(i, _)| i);
+                let split = if split == 0 {
+                    word.chars().next().map_or(0, char::len_utf8)
+                } else {
+                    split
+                };
+
+                lines.push(word[..split].to_string());
+                word = &word[split..];
+                word_width = display_width(word);
+            }
+
+            line.push_str(word);
+            line_width = word_width;
         }
 
-        s
+        lines.push(line);
     }
-}
 
-pub(crate) fn update_params(params: &mut DocParams, arg: &Full) {
-    if let Some(cli) = &arg.cli {
-        match cli {
-            Cli::Short(_) => params.has_short = true,
-            Cli::Long(long) => {
-                params.long_width = Some(
-                    params
-                        .long_width
-                        .map_or(0, usize::from)
-                        .max(long.len())
-                        .try_into()
-                        .unwrap(),
-                );
-            }
-            Cli::Both(_, long) => {
-                params.has_short = true;
-                params.long_width = Some(
-                    params
-                        .long_width
-                        .map_or(0, usize::from)
-                        .max(long.len())
-                        .try_into()
-                        .unwrap(),
-                );
+    let padding = " ".repeat(indent);
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+            if !line.is_empty() {
+                out.push_str(&padding);
             }
         }
+        out.push_str(line);
     }
 
-    if let Some(env) = &arg.env {
-        params.env_width = Some(
-            params
-                .env_width
-                .map_or(0, usize::from)
-                .max(env.len())
-                .try_into()
-                .unwrap(),
-        );
+    out
+}
+
+/// Widens the columns in `params` to fit an argument, given the name of its
+/// value if it takes one.
+pub(crate) fn update_params(params: &mut DocParams, arg: &Full, value: Option<&str>) {
+    if matches!(arg.cli, Some(Cli::Short(_) | Cli::Both(..))) {
+        params.has_short = true;
+    }
+
+    if let Some(long) = long_column(arg, value) {
+        params.long_width = Some(params.long_width.unwrap_or(0).max(display_width(&long)));
     }
 
-    params.max_doc_width = (80
-        - if params.has_short { 3 } else { 0 }
-        - params.long_width.map_or(0, usize::from)
-        - params.env_width.map_or(0, usize::from))
-    .max(12);
+    if let Some(env) = &arg.env {
+        params.env_width = Some(params.env_width.unwrap_or(0).max(display_width(env)));
+    }
 }
 
-pub(crate) fn render_argument(arg: &Full, params: DocParams) -> String {
+/// Renders a single line (or more, if the docs wrap) of the help message,
+/// given the name of the argument's value if it takes one.
+///
+/// If the columns leave too little room for the docs, they're put on the
+/// following lines instead.
+pub(crate) fn render_argument(arg: &Full, value: Option<&str>, params: DocParams) -> String {
+    let theme = params.theme;
     let mut s = String::from(" ");
+    let mut indent = 1;
 
-    if let Some(cli) = &arg.cli {
-        match cli {
-            Cli::Short(short) => {
-                s.push('-');
-                s.push(*short);
-                s.push(' ');
-
-                // s.push_str(" /");
-                s.push_str(&empty(params.long_width));
+    if params.has_short {
+        match &arg.cli {
+            Some(Cli::Short(short) | Cli::Both(short, _)) => {
+                pad(&mut s, &format!("-{short}"), theme.flag, 3);
             }
-            Cli::Long(long) => {
-                if params.has_short {
-                    // s.push_str("   / ");
-                    s.push_str("   ");
-                }
+            _ => s.push_str("   "),
+        }
+        indent += 3;
+    }
 
-                s.push_str("--");
-                s.push_str(long);
-                s.push_str(&" ".repeat(usize::from(params.long_width.unwrap()) - long.len()));
-            }
-            Cli::Both(short, long) => {
-                s.push('-');
-                s.push(*short);
+    if let Some(width) = params.long_width {
+        let (long, placeholder) = long_parts(arg, value);
+        let column = long_column(arg, value).unwrap_or_default();
 
-                // s.push_str(" / ");
+        if let Some(long) = &long {
+            s.push_str(&Theme::paint(theme.flag, long));
+        }
+        if let Some(placeholder) = &placeholder {
+            if long.is_some() {
                 s.push(' ');
+            }
+            s.push_str(&Theme::paint(theme.placeholder, placeholder));
+        }
+        s.push_str(&" ".repeat(width - display_width(&column) + 1));
+        indent += width + 1;
+    }
 
-                s.push_str("--");
-                s.push_str(long);
-                s.push_str(&" ".repeat(usize::from(params.long_width.unwrap()) - long.len()));
+    if let Some(width) = params.env_width {
+        match &arg.env {
+            Some(env) => {
+                pad(&mut s, &format!("${env}"), theme.env, width + 1);
+                s.push(' ');
             }
+            None => s.push_str(&" ".repeat(width + 2)),
+        }
+        indent += width + 2;
+    }
+
+    let mut doc = arg.doc.clone().unwrap_or_default();
+    if let Some(default) = &arg.default_value {
+        if !doc.is_empty() {
+            doc.push(' ');
         }
+        let _ = write!(doc, "[default: {default}]");
+    }
+
+    if doc.is_empty() {
+        s.truncate(s.trim_end().len());
+        return s;
+    }
+
+    let indent = indent + 2;
+    if indent + MIN_DOC_WIDTH <= params.width {
+        s.push_str(": ");
+        s.push_str(&wrap(&doc, params.width - indent, indent));
     } else {
-        if params.has_short {
-            s.push_str("    ");
+        s.truncate(s.trim_end().len());
+        s.push('\n');
+        s.push_str(&" ".repeat(DOC_INDENT));
+        let width = params.width.saturating_sub(DOC_INDENT).max(MIN_DOC_WIDTH);
+        s.push_str(&wrap(&doc, width, DOC_INDENT));
+    }
+
+    s
+}
+
+/// An argument, paired with the name of its value if it takes one.
+pub(crate) type Argument<'a> = (Full, Option<&'a str>);
+
+/// A heading (if any) and the arguments under it.
+type Group<'a, 'b> = (Option<&'a str>, Vec<&'a Argument<'b>>);
+
+/// Groups arguments by their heading, keeping the order they were added in.
+/// Arguments without a heading come first.
+fn group_by_heading<'a, 'b>(args: &'a [Argument<'b>]) -> Vec<Group<'a, 'b>> {
+    let mut groups: Vec<Group> = vec![(None, Vec::new())];
+
+    for arg in args {
+        let heading = arg.0.heading.as_deref();
+        match groups.iter_mut().find(|(h, _)| *h == heading) {
+            Some((_, group)) => group.push(arg),
+            None => groups.push((heading, vec![arg])),
         }
+    }
+
+    groups.retain(|(_, group)| !group.is_empty());
+    groups
+}
 
-        if let Some(width) = params.long_width {
-            s.push_str(&" ".repeat(usize::from(width) + 1));
+/// Renders the full help message, wrapped to `width` columns.
+pub(crate) fn render_help(page: &Page, args: &[Argument], width: usize, theme: Theme) -> String {
+    let mut out = String::new();
+
+    if let Some(version) = page.version {
+        let _ = writeln!(out, "{} {version}", page.name);
+    }
+
+    let usage = format!("{} {}", page.name, page.usage.unwrap_or(DEFAULT_USAGE));
+    let _ = writeln!(out, "{}", Theme::paint(theme.usage, &usage));
+
+    if let Some(doc) = page.doc {
+        out.push_str(&wrap(doc, width, 0));
+        out.push_str("\n\n");
+    }
+
+    let mut params = DocParams {
+        theme,
+        width,
+        has_short: false,
+        long_width: None,
+        env_width: None,
+    };
+    for (tag, value) in args {
+        update_params(&mut params, tag, *value);
+    }
+
+    for (i, (heading, group)) in group_by_heading(args).into_iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+
+        if let Some(heading) = heading {
+            let _ = writeln!(
+                out,
+                "{}",
+                Theme::paint(theme.heading, &format!("{heading}:"))
+            );
+        }
+
+        for (tag, value) in group {
+            out.push_str(&render_argument(tag, *value, params));
+            out.push('\n');
         }
     }
 
-    if let Some(env) = &arg.env {
-        s.push_str(" $");
-        s.push_str(env);
-        s.push_str(&" ".repeat(usize::from(params.env_width.unwrap()) - env.len()));
-    } else {
-        s.push_str(&empty(params.env_width));
+    for (title, lines) in [("Examples", page.examples), ("See also", page.see_also)] {
+        if lines.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(
+            out,
+            "\n{}",
+            Theme::paint(theme.heading, &format!("{title}:"))
+        );
+        for line in lines {
+            let _ = writeln!(out, "  {}", wrap(line, width.saturating_sub(2), 2));
+        }
     }
 
-    if let Some(doc) = &arg.doc {
-        s.push_str(&wrap_doc(doc, params));
+    out
+}
+
+/// Escapes text for roff, including control characters at the start of
+/// lines. Blank lines become paragraph breaks.
+fn roff_escape(text: &str) -> String {
+    let mut out = String::new();
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            out.push_str(".sp\n");
+            continue;
+        }
+
+        if line.starts_with(['.', '\'']) {
+            out.push_str("\\&");
+        }
+
+        out.push_str(&line.replace('\\', "\\e").replace('-', "\\-"));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Renders the bold flags of a CLI tag, followed by a placeholder if it
+/// takes a value, e.g. `\fB\-f\fR, \fB\-\-foo\fR \fIN\fR`.
+fn roff_flags(cli: &Cli, value: Option<&str>) -> String {
+    let mut s = match cli {
+        Cli::Short(short) => format!("\\fB\\-{short}\\fR"),
+        Cli::Long(long) => format!("\\fB\\-\\-{}\\fR", long.replace('-', "\\-")),
+        Cli::Both(short, long) => format!(
+            "\\fB\\-{short}\\fR, \\fB\\-\\-{}\\fR",
+            long.replace('-', "\\-")
+        ),
+    };
+
+    if let Some(value) = value {
+        let _ = write!(s, " \\fI{}\\fR", value.replace('-', "\\-"));
     }
 
     s
 }
+
+/// Renders a man page in section 1 for the program. Each argument is paired
+/// with the name of its value, if it takes one.
+pub(crate) fn render_manpage(page: &Page, args: &[Argument]) -> String {
+    let mut out = String::new();
+    let name = page.name.replace('-', "\\-");
+
+    let _ = write!(out, ".TH {} 1", name.to_uppercase());
+    if let Some(version) = page.version {
+        let _ = write!(out, " \"\" \"{name} {}\"", version.replace('-', "\\-"));
+    }
+    out.push('\n');
+
+    out.push_str(".SH NAME\n");
+    out.push_str(&name);
+    if let Some(summary) = page.doc.and_then(|doc| doc.lines().next()) {
+        out.push_str(" \\- ");
+        out.push_str(&roff_escape(summary));
+    } else {
+        out.push('\n');
+    }
+
+    out.push_str(".SH SYNOPSIS\n");
+    let _ = writeln!(out, ".B {name}");
+    match page.usage {
+        Some(usage) => out.push_str(&roff_escape(usage)),
+        None => out.push_str("[\\fIoptions\\fR...] [\\fIarguments\\fR...]\n"),
+    }
+
+    if let Some(doc) = page.doc {
+        out.push_str(".SH DESCRIPTION\n");
+        out.push_str(&roff_escape(doc));
+    }
+
+    let options: Vec<_> = args
+        .iter()
+        .filter(|(tag, _)| tag.cli.is_some())
+        .cloned()
+        .collect();
+    if !options.is_empty() {
+        out.push_str(".SH OPTIONS\n");
+        for (heading, group) in group_by_heading(&options) {
+            if let Some(heading) = heading {
+                let _ = writeln!(out, ".SS {}", roff_escape(heading).trim_end());
+            }
+
+            for (tag, value) in group {
+                let Some(cli) = &tag.cli else { continue };
+
+                out.push_str(".TP\n");
+                out.push_str(&roff_flags(cli, *value));
+                out.push('\n');
+
+                if let Some(doc) = &tag.doc {
+                    out.push_str(&roff_escape(doc));
+                }
+            }
+        }
+    }
+
+    if args.iter().any(|(tag, _)| tag.env.is_some()) {
+        out.push_str(".SH ENVIRONMENT\n");
+        for (tag, _) in args {
+            let Some(env) = &tag.env else { continue };
+
+            out.push_str(".TP\n");
+            let _ = writeln!(out, ".B {}", roff_escape(env).trim_end());
+
+            if let Some(doc) = &tag.doc {
+                out.push_str(&roff_escape(doc));
+            } else if let Some(cli) = &tag.cli {
+                let _ = writeln!(out, "Same as {}.", roff_flags(cli, None));
+            }
+        }
+    }
+
+    if !page.examples.is_empty() {
+        out.push_str(".SH EXAMPLES\n");
+        for example in page.examples {
+            out.push_str(".PP\n.nf\n");
+            out.push_str(&roff_escape(example));
+            out.push_str(".fi\n");
+        }
+    }
+
+    if !page.see_also.is_empty() {
+        out.push_str(".SH SEE ALSO\n");
+        let refs: Vec<_> = page
+            .see_also
+            .iter()
+            .map(|reference| roff_escape(reference).trim_end().to_string())
+            .collect();
+        out.push_str(&refs.join(",\n"));
+        out.push('\n');
+    }
+
+    out
+}
@@ -11,8 +11,11 @@
 pub mod prelude;
 
 use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
 use std::marker::PhantomData;
 use std::ops::Deref;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "macros")]
 pub mod macros;
@@ -23,13 +26,38 @@ use tag::Full;
 mod error;
 pub use error::ArgParseError;
 
+mod source;
+pub use source::{MergePolicy, ValueSource};
+
+mod config;
+mod shell;
+
+mod style;
+pub use style::{ColorChoice, Theme};
+
 #[cfg(feature = "help")]
 mod help;
+
+#[cfg(feature = "help")]
+mod schema;
 #[cfg(feature = "help")]
-use help::DocParams;
+pub use schema::{ArgumentSchema, Schema};
+
+#[cfg(feature = "completions")]
+mod completions;
+#[cfg(feature = "completions")]
+pub use completions::{Completer, Shell, UnknownShell};
 
-mod types;
-pub use types::{ArgResult, ArgumentType, DefaultedArgResult};
+pub mod types;
+pub use types::{ArgResult, ArgumentType, DefaultedArgResult, Delimiter};
+
+/// Returns the file stem of a path, e.g. `app` for `/usr/bin/app.exe`.
+#[cfg(feature = "help")]
+fn file_stem(path: &OsStr) -> Option<String> {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
 
 #[doc(hidden)]
 pub trait __SargeDefault<T> {
@@ -83,17 +111,112 @@ pub fn __sarge_default_expr<T>(d: impl __SargeDefaultExpr<T>) -> T {
     d.__sarge_default_expr()
 }
 
+/// A default value in `sarge!`, paired with its source text, to be shown in
+/// help. It's rendered with `Display` if possible, then `Debug`, and
+/// otherwise the source text; the traits below are picked by autoref, so
+/// call `(&&&__SargeDefaultText(..)).__sarge_text()`.
+#[doc(hidden)]
+pub struct __SargeDefaultText<'a, T>(pub &'a T, pub &'static str);
+
+#[doc(hidden)]
+pub trait __SargeDisplayText {
+    fn __sarge_text(&self) -> String;
+}
+
+impl<T: std::fmt::Display> __SargeDisplayText for &&__SargeDefaultText<'_, T> {
+    fn __sarge_text(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[doc(hidden)]
+pub trait __SargeDebugText {
+    fn __sarge_text(&self) -> String;
+}
+
+impl<T: std::fmt::Debug> __SargeDebugText for &__SargeDefaultText<'_, T> {
+    fn __sarge_text(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait __SargeSourceText {
+    fn __sarge_text(&self) -> String;
+}
+
+impl<T> __SargeSourceText for __SargeDefaultText<'_, T> {
+    fn __sarge_text(&self) -> String {
+        self.1.to_string()
+    }
+}
+
 #[cfg(test)]
 mod test;
 
 #[derive(Clone, Debug)]
-#[allow(clippy::option_option)]
+#[allow(clippy::struct_excessive_bools)]
 struct InternalArgument {
     tag: Full,
     consumes: bool,
     repeatable: bool,
-    cli_set: bool,
-    val: Option<Option<String>>,
+    non_utf8: bool,
+    delimiter: Delimiter,
+    #[cfg(feature = "help")]
+    value_name: &'static str,
+    source: ValueSource,
+    val: Option<Vec<OsString>>,
+}
+
+impl InternalArgument {
+    /// Returns the name of the value in help messages.
+    #[cfg(feature = "help")]
+    fn value_name(&self) -> &str {
+        self.tag.value_name.as_deref().unwrap_or(self.value_name)
+    }
+
+    /// Returns the value as an `OsString`, or an error if it isn't valid UTF-8
+    /// and this argument requires it to be.
+    fn check_utf8(&self, val: &OsStr) -> Result<OsString, ArgParseError> {
+        if self.non_utf8 || val.to_str().is_some() {
+            Ok(val.to_os_string())
+        } else {
+            Err(ArgParseError::InvalidUnicode(
+                val.to_string_lossy().into_owned(),
+            ))
+        }
+    }
+
+    /// Sets a value given on the CLI at index `i`, merging it with any
+    /// existing value according to the tag's [`MergePolicy`] (or `default`).
+    fn set_cli(&mut self, i: usize, val: Option<OsString>, default: MergePolicy) {
+        match (self.tag.merge_policy.unwrap_or(default), &self.source) {
+            (MergePolicy::EnvWins, ValueSource::Env(_)) => return,
+            (MergePolicy::Append, _) if self.repeatable => {}
+            (_, source) if !source.is_cli() => self.val = None,
+            _ => {}
+        }
+
+        self.source = ValueSource::Cli(i);
+        self.set(val);
+    }
+
+    /// Sets a value, appending it if repeatable.
+    fn set(&mut self, val: Option<OsString>) {
+        if self.repeatable {
+            if let Some(val) = val {
+                self.val.get_or_insert_with(Vec::new).push(val);
+            }
+        } else {
+            self.val = Some(val.into_iter().collect());
+        }
+    }
+
+    /// Parses the value(s), if any were given.
+    fn parse<T: ArgumentType>(&self) -> ArgResult<T> {
+        let vals: Vec<_> = self.val.as_ref()?.iter().map(OsString::as_os_str).collect();
+        T::from_os_values(&vals, self.delimiter)
+    }
 }
 
 /// The results of [`ArgumentReader::parse`]. Used both for retrieving
@@ -106,6 +229,7 @@ struct InternalArgument {
 pub struct Arguments {
     args: Vec<InternalArgument>,
     remainder: Vec<String>,
+    remainder_os: Vec<OsString>,
 }
 
 impl AsRef<[String]> for Arguments {
@@ -129,6 +253,12 @@ impl From<Arguments> for Vec<String> {
     }
 }
 
+impl From<Arguments> for Vec<OsString> {
+    fn from(args: Arguments) -> Vec<OsString> {
+        args.remainder_os
+    }
+}
+
 impl Arguments {
     /// All the CLI arguments that didn't get parsed as part of an argument.
     ///
@@ -139,6 +269,15 @@ impl Arguments {
         self
     }
 
+    /// All the CLI arguments that didn't get parsed as part of an argument,
+    /// exactly as they were given.
+    ///
+    /// Unlike [`remainder`](Arguments::remainder), these haven't been lossily
+    /// converted to UTF-8.
+    pub fn remainder_os(&self) -> &[OsString] {
+        &self.remainder_os
+    }
+
     pub(crate) fn get_arg(&self, i: usize) -> &InternalArgument {
         &self.args[i]
     }
@@ -162,10 +301,11 @@ impl<T: ArgumentType> ArgumentRef<T> {
     /// If there was no value given to the argument,
     /// returns `None`.
     ///
-    /// For `String` and `bool`, this can never fail.
+    /// For `String`, this can never fail.
     pub fn get(&self, args: &Arguments) -> ArgResult<T> {
-        if let Some(val) = &args.get_arg(self.i).val {
-            T::from_value(val.as_deref())
+        let arg = args.get_arg(self.i);
+        if arg.val.is_some() {
+            arg.parse()
         } else {
             T::default_value().map(Ok)
         }
@@ -178,11 +318,12 @@ impl<T: ArgumentType> ArgumentRef<T> {
     /// (`#ok`/`#err`) and macro-provided defaults to take precedence.
     #[doc(hidden)]
     pub fn get_raw(&self, args: &Arguments) -> ArgResult<T> {
-        if let Some(val) = &args.get_arg(self.i).val {
-            T::from_value(val.as_deref())
-        } else {
-            None
-        }
+        args.get_arg(self.i).parse()
+    }
+
+    /// Retrieve where the value of the argument came from.
+    pub fn source<'a>(&self, args: &'a Arguments) -> &'a ValueSource {
+        &args.get_arg(self.i).source
     }
 
     /// Retrieve the tag of the argument from an [`Arguments`].
@@ -197,13 +338,40 @@ impl<T: ArgumentType> ArgumentRef<T> {
 /// The structure that actually reads all your arguments.
 ///
 /// Use [`ArgumentReader::add`] to register arguments and get [`ArgumentRef`]s.
-/// Then, use <code>[ArgumentReader::parse]{_cli,_env,_provided}</code> to get
+/// Then, use <code>[ArgumentReader::parse]{,_provided,_provided_os}</code> to get
 /// [`Arguments`], which contains the results of parsing. Finally, you can use
 /// [`ArgumentRef::get`] to retrieve the values of your arguments.
+///
+/// Parsing consumes the reader; to parse many command lines with the same
+/// reader, use [`parse_ref`](ArgumentReader::parse_ref) instead.
 #[derive(Debug, Clone, Default)]
-#[allow(clippy::doc_markdown)]
+#[allow(clippy::doc_markdown, clippy::struct_excessive_bools)]
 pub struct ArgumentReader {
     args: Vec<InternalArgument>,
+    arg_files: bool,
+    env_prefix: Option<String>,
+    merge_policy: MergePolicy,
+    version: Option<String>,
+    #[cfg(feature = "help")]
+    name: Option<String>,
+    #[cfg(feature = "help")]
+    help_width: Option<usize>,
+    #[cfg(feature = "help")]
+    color: ColorChoice,
+    #[cfg(feature = "help")]
+    theme: Theme,
+    #[cfg(feature = "help")]
+    auto_help: bool,
+    #[cfg(feature = "help")]
+    auto_version: bool,
+    #[cfg(feature = "completions")]
+    dynamic_completion: bool,
+    #[cfg(feature = "help")]
+    usage: Option<String>,
+    #[cfg(feature = "help")]
+    examples: Vec<String>,
+    #[cfg(feature = "help")]
+    see_also: Vec<String>,
 
     /// Program-level documentation.
     ///
@@ -216,69 +384,457 @@ impl ArgumentReader {
     pub fn new() -> Self {
         Self {
             args: Vec::new(),
+            arg_files: false,
+            env_prefix: None,
+            merge_policy: MergePolicy::Override,
+            version: None,
+            #[cfg(feature = "help")]
+            name: None,
+            #[cfg(feature = "help")]
+            help_width: None,
+            #[cfg(feature = "help")]
+            color: ColorChoice::Never,
+            #[cfg(feature = "help")]
+            theme: Theme::default(),
+            #[cfg(feature = "help")]
+            auto_help: false,
+            #[cfg(feature = "help")]
+            auto_version: false,
+            #[cfg(feature = "completions")]
+            dynamic_completion: false,
+            #[cfg(feature = "help")]
+            usage: None,
+            #[cfg(feature = "help")]
+            examples: Vec::new(),
+            #[cfg(feature = "help")]
+            see_also: Vec::new(),
             doc: None,
         }
     }
 
-    /// Returns help for all the arguments.
+    /// Automatically binds every argument with a long form, but no explicit
+    /// environment variable, to `{prefix}{LONG_FORM}`. The long form is
+    /// uppercased and has its dashes and dots replaced with underscores, so
+    /// with the prefix `MYAPP_`, `--log-level` is bound to `MYAPP_LOG_LEVEL`
+    /// and `--net.port` (e.g. from a config `[net]` section) to
+    /// `MYAPP_NET_PORT`.
+    pub fn env_prefix<S: Into<String>>(&mut self, prefix: S) -> &mut Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets how CLI arguments are merged with values from environment
+    /// variables (and configuration files). Defaults to
+    /// [`MergePolicy::Override`]; individual arguments may override this via
+    /// [`Full::merge_policy`].
+    pub fn merge_policy(&mut self, policy: MergePolicy) -> &mut Self {
+        self.merge_policy = policy;
+        self
+    }
+
+    /// Sets the name of the program shown in help messages, man pages and
+    /// completion scripts. Otherwise, it's the name of the Cargo binary
+    /// (when built by Cargo), the name of the current executable, or
+    /// `argv[0]`, in that order.
     ///
     /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn name<S: Into<String>>(&mut self, name: S) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Returns the name of the program. See [`name`](ArgumentReader::name).
+    #[cfg(feature = "help")]
+    fn program_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| option_env!("CARGO_BIN_NAME").map(String::from))
+            .or_else(|| file_stem(env::current_exe().ok()?.as_os_str()))
+            .or_else(|| file_stem(&env::args_os().next()?))
+            .unwrap_or_else(|| "program".to_string())
+    }
+
+    /// Sets the version of the program, shown at the top of help messages.
+    pub fn version<S: Into<String>>(&mut self, version: S) -> &mut Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Enables or disables a built-in `-h`/`--help` flag. Disabled by
+    /// default. When given, parsing stops and returns
+    /// [`ArgParseError::HelpRequested`] with the rendered help. Either form
+    /// is left out if an argument already uses it.
     ///
-    /// # Panics
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn auto_help(&mut self, enabled: bool) -> &mut Self {
+        self.auto_help = enabled;
+        self
+    }
+
+    /// Enables or disables a built-in `-V`/`--version` flag, which works
+    /// like [`auto_help`](ArgumentReader::auto_help) but returns
+    /// [`ArgParseError::VersionRequested`] with the program's name and
+    /// [`version`](ArgumentReader::version). Disabled by default.
     ///
-    /// If the name of the executable could not be found, panics.
+    /// Only available on feature `help`.
     #[cfg(feature = "help")]
-    pub fn help(&self) -> String {
-        let exe = option_env!("CARGO_BIN_NAME")
-            .map(String::from)
-            .or_else(|| {
-                std::env::current_exe()
-                    .ok()
-                    .and_then(|s| s.file_stem().map(|s| s.to_string_lossy().into_owned()))
+    pub fn auto_version(&mut self, enabled: bool) -> &mut Self {
+        self.auto_version = enabled;
+        self
+    }
+
+    /// Returns the tags of the enabled built-in flags, each paired with its
+    /// canonical name (`help` or `version`), leaving out any forms already
+    /// used by an argument.
+    #[cfg(feature = "help")]
+    fn builtin_tags(&self) -> Vec<(&'static str, Full)> {
+        let builtins = [
+            (self.auto_help, 'h', "help", "Print help"),
+            (self.auto_version, 'V', "version", "Print version"),
+        ];
+
+        builtins
+            .into_iter()
+            .filter(|(enabled, ..)| *enabled)
+            .filter_map(|(_, short, long, doc)| {
+                let short_free = !self.args.iter().any(|arg| arg.tag.matches_short(short));
+                let long_free = !self.args.iter().any(|arg| arg.tag.matches_long(long));
+                let cli = match (short_free, long_free) {
+                    (true, true) => tag::Cli::Both(short, long.to_string()),
+                    (true, false) => tag::Cli::Short(short),
+                    (false, true) => tag::Cli::Long(long.to_string()),
+                    (false, false) => return None,
+                };
+
+                Some((long, Full::from(cli).doc(doc)))
             })
-            .expect("failed to get executable");
+            .collect()
+    }
 
-        let mut out = String::new();
-        out.push_str(&exe);
-        out.push_str(" [options...] <arguments...>\n");
+    /// If `matches` matches a built-in flag, returns the error that stops
+    /// parsing.
+    #[cfg(feature = "help")]
+    fn builtin_flag(&self, matches: impl Fn(&Full) -> bool) -> Option<ArgParseError> {
+        let (name, _) = self
+            .builtin_tags()
+            .into_iter()
+            .find(|(_, tag)| matches(tag))?;
 
-        if let Some(doc) = &self.doc {
-            out.push_str(doc);
-            out.push_str("\n\n");
-        }
+        Some(if name == "help" {
+            ArgParseError::HelpRequested(self.help())
+        } else {
+            let name = self.program_name();
+            ArgParseError::VersionRequested(match &self.version {
+                Some(version) => format!("{name} {version}\n"),
+                None => format!("{name}\n"),
+            })
+        })
+    }
 
-        let mut params = DocParams::default();
-        for arg in &self.args {
-            help::update_params(&mut params, &arg.tag);
-        }
+    /// Sets the usage synopsis shown after the executable name in help
+    /// messages, e.g. `"[options...] <input> [output]"`. Defaults to
+    /// `"[options...] <arguments...>"`.
+    ///
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn usage<S: Into<String>>(&mut self, usage: S) -> &mut Self {
+        self.usage = Some(usage.into());
+        self
+    }
 
-        for arg in &self.args {
-            out.push_str(&help::render_argument(&arg.tag, params));
-            out.push('\n');
-        }
+    /// Adds an example invocation to the "Examples" section at the end of
+    /// help messages, e.g. `"my-app --fast input.txt"`.
+    ///
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn example<S: Into<String>>(&mut self, example: S) -> &mut Self {
+        self.examples.push(example.into());
+        self
+    }
 
-        out
+    /// Adds a related command or page to the "See also" section at the end
+    /// of help messages, e.g. `"git(1)"`.
+    ///
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn see_also<S: Into<String>>(&mut self, reference: S) -> &mut Self {
+        self.see_also.push(reference.into());
+        self
     }
 
-    /// Prints help for all the arguments.
+    /// Sets the width, in columns, that help messages are wrapped to.
+    /// Defaults to the `COLUMNS` environment variable if it's set, or 80
+    /// otherwise.
     ///
     /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn help_width(&mut self, width: usize) -> &mut Self {
+        self.help_width = Some(width);
+        self
+    }
+
+    /// Sets when help messages are styled with ANSI escape codes, using the
+    /// [`theme`](ArgumentReader::theme). [`ColorChoice::Auto`] styles them
+    /// only when stdout is a terminal. Defaults to [`ColorChoice::Never`].
     ///
-    /// # Panics
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn color(&mut self, color: ColorChoice) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the styles used for help messages when they're
+    /// [colored](ArgumentReader::color). Defaults to [`Theme::default`].
     ///
-    /// If the name of the executable could not be found, panics.
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Returns the tags of all the arguments, with environment variables
+    /// derived from [`env_prefix`](ArgumentReader::env_prefix) if set.
+    #[cfg(feature = "help")]
+    fn tags(&self) -> Vec<Full> {
+        self.args
+            .iter()
+            .map(|arg| {
+                let mut tag = arg.tag.clone();
+                if let Some(prefix) = &self.env_prefix {
+                    tag.derive_env(prefix);
+                }
+                tag
+            })
+            .collect()
+    }
+
+    /// Returns everything about the program shown in help messages, besides
+    /// the arguments.
+    #[cfg(feature = "help")]
+    fn page(&self) -> help::Page<'_> {
+        help::Page {
+            name: self.program_name(),
+            version: self.version.as_deref(),
+            usage: self.usage.as_deref(),
+            doc: self.doc.as_deref(),
+            examples: &self.examples,
+            see_also: &self.see_also,
+        }
+    }
+
+    /// Returns the tags of all the arguments (see
+    /// [`tags`](ArgumentReader::tags)) and built-in flags, each paired with
+    /// the name of its value if it takes one.
+    #[cfg(feature = "help")]
+    fn documented_args(&self) -> Vec<help::Argument<'_>> {
+        self.tags()
+            .into_iter()
+            .zip(&self.args)
+            .map(|(tag, arg)| (tag, arg.consumes.then(|| arg.value_name())))
+            .chain(self.builtin_tags().into_iter().map(|(_, tag)| (tag, None)))
+            .collect()
+    }
+
+    /// Enables or disables expanding `@path` CLI arguments into the
+    /// arguments contained in that file, like `rustc` and `javac` do.
+    /// Disabled by default.
+    ///
+    /// Arguments in the file are separated by whitespace (including
+    /// newlines), and may be quoted and escaped like in a POSIX shell; `#`
+    /// starts a comment. Files may include other files, up to a depth of
+    /// 16. Relative paths are relative to the current directory.
+    pub fn arg_files(&mut self, enabled: bool) -> &mut Self {
+        self.arg_files = enabled;
+        self
+    }
+
+    /// Returns help for all the arguments.
+    ///
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn help(&self) -> String {
+        help::render_help(
+            &self.page(),
+            &self.documented_args(),
+            help::terminal_width(self.help_width),
+            if self.color.should_color(&std::io::stdout()) {
+                self.theme
+            } else {
+                Theme::plain()
+            },
+        )
+    }
+
+    /// Prints help for all the arguments.
+    ///
+    /// Only available on feature `help`.
     #[cfg(feature = "help")]
     pub fn print_help(&self) {
         print!("{}", self.help());
     }
 
+    /// Returns a man page (in roff format, section 1) for the program,
+    /// with the sections NAME, SYNOPSIS, DESCRIPTION (from
+    /// [`doc`](ArgumentReader::doc)), OPTIONS and ENVIRONMENT (from the
+    /// arguments' docs).
+    ///
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn manpage(&self) -> String {
+        help::render_manpage(&self.page(), &self.documented_args())
+    }
+
+    /// Returns a description of the program and all of its arguments, which
+    /// can be exported as Markdown or JSON.
+    ///
+    /// Only available on feature `help`.
+    #[cfg(feature = "help")]
+    pub fn schema(&self) -> Schema {
+        let arguments = self
+            .tags()
+            .iter()
+            .zip(&self.args)
+            .map(|(tag, arg)| {
+                let value_name = arg.consumes.then(|| arg.value_name());
+                schema::ArgumentSchema::new(tag, value_name, arg.consumes, arg.repeatable)
+            })
+            .collect();
+
+        Schema {
+            name: self.program_name(),
+            doc: self.doc.clone(),
+            arguments,
+        }
+    }
+
+    /// Returns a script for the given shell which completes the flags of
+    /// all the arguments, using their docs as descriptions. Flags that take
+    /// a value complete file names after them.
+    ///
+    /// Only available on feature `completions`.
+    #[cfg(feature = "completions")]
+    pub fn completions(&self, shell: Shell) -> String {
+        let tags = self.completion_tags();
+        let args: Vec<_> = tags
+            .iter()
+            .filter_map(|(tag, consumes, repeatable)| {
+                completions::Completion::new(tag, *consumes, *repeatable)
+            })
+            .collect();
+
+        completions::render(shell, &self.program_name(), &args)
+    }
+
+    /// Returns the tags of all the arguments (see
+    /// [`tags`](ArgumentReader::tags)) and built-in flags, each paired with
+    /// whether it consumes a value and whether it's repeatable.
+    #[cfg(feature = "completions")]
+    fn completion_tags(&self) -> Vec<(Full, bool, bool)> {
+        self.tags()
+            .into_iter()
+            .zip(&self.args)
+            .map(|(tag, arg)| (tag, arg.consumes, arg.repeatable))
+            .chain(
+                self.builtin_tags()
+                    .into_iter()
+                    .map(|(_, tag)| (tag, false, false)),
+            )
+            .collect()
+    }
+
+    /// Prints a completion script for the given shell.
+    ///
+    /// Only available on feature `completions`.
+    #[cfg(feature = "completions")]
+    pub fn print_completions(&self, shell: Shell) {
+        print!("{}", self.completions(shell));
+    }
+
+    /// Enables or disables dynamic completion in
+    /// [`parse`](ArgumentReader::parse): when the environment variable
+    /// `SARGE_COMPLETE` is set to a [`Shell`], it prints completion
+    /// candidates and exits the process instead of parsing. Disabled by
+    /// default; see [`complete`](ArgumentReader::complete).
+    ///
+    /// Only available on feature `completions`.
+    #[cfg(feature = "completions")]
+    pub fn dynamic_completion(&mut self, enabled: bool) -> &mut Self {
+        self.dynamic_completion = enabled;
+        self
+    }
+
+    /// Returns the candidates for dynamic completion, one per line, in the
+    /// format expected by the given shell. `words` are the CLI arguments
+    /// (excluding the executable) up to and including the one under the
+    /// cursor, which is completed as a flag, or as a value using the
+    /// argument's [`completer`](Full::completer).
+    ///
+    /// If `words` is empty, instead returns a script to register with the
+    /// shell, e.g. via `source <(SARGE_COMPLETE=bash my-app)`. That script
+    /// runs the program with `SARGE_COMPLETE` set to the shell's name,
+    /// which makes [`parse`](ArgumentReader::parse) print the result of this
+    /// method and exit, if
+    /// [`dynamic_completion`](ArgumentReader::dynamic_completion) is enabled.
+    ///
+    /// Only available on feature `completions`.
+    #[cfg(feature = "completions")]
+    pub fn complete<S: Into<String>, I: IntoIterator<Item = S>>(
+        &self,
+        shell: Shell,
+        words: I,
+    ) -> String {
+        let words: Vec<String> = words.into_iter().map(Into::into).collect();
+        if words.is_empty() {
+            return completions::register(shell, &self.program_name());
+        }
+
+        let tags = self.completion_tags();
+        let args: Vec<_> = tags
+            .iter()
+            .filter_map(|(tag, consumes, repeatable)| {
+                completions::Completion::new(tag, *consumes, *repeatable)
+            })
+            .collect();
+
+        completions::candidates(shell, words, &args)
+    }
+
+    /// Returns what [`parse`](ArgumentReader::parse) should print for
+    /// dynamic completion, given the value of `SARGE_COMPLETE` and the CLI
+    /// arguments (excluding the executable), or `None` to parse normally.
+    #[cfg(feature = "completions")]
+    pub(crate) fn dynamic_complete<I: IntoIterator<Item = OsString>>(
+        &self,
+        shell: Option<OsString>,
+        words: I,
+    ) -> Option<String> {
+        let shell = shell
+            .filter(|_| self.dynamic_completion)?
+            .to_str()?
+            .parse()
+            .ok()?;
+        let words = words
+            .into_iter()
+            .map(|word| word.to_string_lossy().into_owned());
+
+        Some(self.complete(shell, words))
+    }
+
     /// Adds an argument to the parser.
     pub fn add<T: ArgumentType>(&mut self, tag: Full) -> ArgumentRef<T> {
         let arg = InternalArgument {
+            delimiter: tag.delimiter.unwrap_or(T::DELIMITER),
             tag,
             consumes: T::CONSUMES,
             repeatable: T::REPEATABLE,
-            cli_set: false,
+            non_utf8: T::ACCEPTS_NON_UTF8,
+            #[cfg(feature = "help")]
+            value_name: T::VALUE_NAME,
+            source: ValueSource::Default,
             val: None,
         };
 
@@ -291,7 +847,98 @@ impl ArgumentReader {
         }
     }
 
-    /// Parse arguments from `std::env::{args,vars}`.
+    /// Load values from a configuration file's contents, to be overridden
+    /// by environment variables and CLI arguments when parsing.
+    ///
+    /// The format is a small subset of INI/TOML:
+    ///
+    /// ```plain
+    /// # Comments start with `#` or `;`.
+    /// name = "quoted \"value\""
+    /// verbose = true
+    /// tags = [a, "b c", 'd']
+    ///
+    /// [net]
+    /// port = 8080
+    /// ```
+    ///
+    /// Keys are matched against long tag names, with underscores also
+    /// matching dashes (so `log_level` sets `--log-level`). Keys under a
+    /// `[section]` are prefixed with `section.` (so the above sets
+    /// `--net.port`). Arrays may only be given to repeatable arguments, such
+    /// as `Vec<T>`; repeating a key appends to those, and replaces any
+    /// other. Each array item is a single element, even if it contains the
+    /// argument's [`Delimiter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArgParseError::InvalidConfig`] with the offending line
+    /// number if the file is malformed, contains an unknown key, or gives an
+    /// array to a non-repeatable argument.
+    pub fn parse_config(&mut self, src: &str) -> Result<(), ArgParseError> {
+        for entry in config::parse(src)? {
+            let err = |msg: String| ArgParseError::InvalidConfig(entry.line, msg);
+            let dashed = entry.key.replace('_', "-");
+
+            let arg = self
+                .args
+                .iter_mut()
+                .find(|arg| arg.tag.matches_long(&entry.key) || arg.tag.matches_long(&dashed))
+                .ok_or_else(|| err(format!("unknown key `{}`", entry.key)))?;
+
+            if arg.repeatable {
+                // array items are already split, so protect them from the
+                // argument's delimiter
+                let delimiter = if entry.array {
+                    arg.delimiter
+                } else {
+                    Delimiter::None
+                };
+                arg.val.get_or_insert_with(Vec::new).extend(
+                    entry
+                        .values
+                        .iter()
+                        .map(|val| OsString::from(delimiter.escape(val))),
+                );
+            } else if let [val] = &entry.values[..] {
+                arg.val = Some(vec![OsString::from(val)]);
+            } else {
+                return Err(err(format!("`{}` doesn't take a list", entry.key)));
+            }
+
+            arg.source = ValueSource::Config(entry.line);
+        }
+
+        Ok(())
+    }
+
+    /// Load values from a configuration file; see
+    /// [`parse_config`](ArgumentReader::parse_config) for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArgParseError::Io`] if the file couldn't be read, and
+    /// otherwise forwards any errors from
+    /// [`parse_config`](ArgumentReader::parse_config).
+    pub fn parse_config_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ArgParseError> {
+        let path = path.as_ref();
+        let src = fs::read_to_string(path)
+            .map_err(|e| ArgParseError::Io(path.to_path_buf(), e.kind()))?;
+
+        self.parse_config(&src)
+    }
+
+    /// Parse arguments from `std::env::{args_os,vars_os}`.
+    ///
+    /// Values that aren't valid UTF-8 are kept as-is for arguments that
+    /// accept them (see [`ArgumentType::ACCEPTS_NON_UTF8`]), and rejected
+    /// otherwise; remainder arguments are converted lossily (see
+    /// [`Arguments::remainder_os`] for the originals).
+    ///
+    /// If [`dynamic_completion`](ArgumentReader::dynamic_completion) is
+    /// enabled and the environment variable `SARGE_COMPLETE` is set to a
+    /// [`Shell`], instead prints completion candidates for the CLI arguments
+    /// and exits; see [`complete`](ArgumentReader::complete).
     ///
     /// # Errors
     ///
@@ -299,7 +946,16 @@ impl ArgumentReader {
     /// will forward that error. Otherwise, see
     /// [`ArgParseError`] for a list of all possible errors.
     pub fn parse(self) -> Result<Arguments, ArgParseError> {
-        self.parse_provided(env::args(), env::vars())
+        #[cfg(feature = "completions")]
+        if let Some(candidates) = self.dynamic_complete(
+            env::var_os(completions::COMPLETE_VAR),
+            env::args_os().skip(1),
+        ) {
+            print!("{candidates}");
+            std::process::exit(0);
+        }
+
+        self.parse_provided_os(env::args_os(), env::vars_os())
     }
 
     /// Parse from the provided environment variables and CLI arguments.
@@ -315,109 +971,237 @@ impl ArgumentReader {
         K: AsRef<str>,
         V: AsRef<str>,
         IE: IntoIterator<Item = (K, V)>,
+    >(
+        self,
+        cli: IA,
+        env: IE,
+    ) -> Result<Arguments, ArgParseError> {
+        self.parse_provided_os(
+            cli.into_iter().map(|arg| arg.as_ref().to_string()),
+            env.into_iter()
+                .map(|(key, val)| (key.as_ref().to_string(), val.as_ref().to_string())),
+        )
+    }
+
+    /// Parse from the provided environment variables and CLI arguments,
+    /// which may not be valid UTF-8.
+    ///
+    /// See [`parse`](ArgumentReader::parse) for how non-UTF-8 values are
+    /// handled.
+    ///
+    /// # Errors
+    ///
+    /// If any arguments fail to parse their values, this
+    /// will forward that error. Otherwise, see
+    /// [`ArgParseError`] for a list of all possible errors.
+    pub fn parse_provided_os<
+        A: AsRef<OsStr>,
+        IA: IntoIterator<Item = A>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+        IE: IntoIterator<Item = (K, V)>,
     >(
         mut self,
         cli: IA,
         env: IE,
     ) -> Result<Arguments, ArgParseError> {
-        self.parse_env(env);
+        self.parse_env(env)?;
         self.parse_cli(cli)
     }
 
+    /// Parse from the provided environment variables and CLI arguments,
+    /// leaving the reader untouched so it can be reused, e.g. for every line
+    /// of a REPL.
+    ///
+    /// Any values loaded via [`parse_config`](ArgumentReader::parse_config)
+    /// are kept for every parse.
+    ///
+    /// # Errors
+    ///
+    /// See [`parse_provided`](ArgumentReader::parse_provided) for details.
+    pub fn parse_ref<
+        A: AsRef<str>,
+        IA: IntoIterator<Item = A>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+        IE: IntoIterator<Item = (K, V)>,
+    >(
+        &self,
+        cli: IA,
+        env: IE,
+    ) -> Result<Arguments, ArgParseError> {
+        self.clone().parse_provided(cli, env)
+    }
+
+    /// Like [`parse_ref`](ArgumentReader::parse_ref), but the arguments may
+    /// not be valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// See [`parse_provided_os`](ArgumentReader::parse_provided_os) for
+    /// details.
+    pub fn parse_ref_os<
+        A: AsRef<OsStr>,
+        IA: IntoIterator<Item = A>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+        IE: IntoIterator<Item = (K, V)>,
+    >(
+        &self,
+        cli: IA,
+        env: IE,
+    ) -> Result<Arguments, ArgParseError> {
+        self.clone().parse_provided_os(cli, env)
+    }
+
+    /// Parse a single command line, such as `deploy --env 'prod east' -f`,
+    /// leaving the reader untouched (see
+    /// [`parse_ref`](ArgumentReader::parse_ref)). Environment variables
+    /// aren't read.
+    ///
+    /// The string is split into arguments like a POSIX shell would, minus
+    /// any expansions: words are separated by whitespace, single quotes
+    /// preserve everything literally, double quotes allow escaping `"` and
+    /// `\`, and a backslash escapes any character outside of quotes. Unlike
+    /// [`parse`](ArgumentReader::parse), the first word isn't assumed to be
+    /// the executable name; it simply ends up in the remainder like any
+    /// other.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArgParseError::UnterminatedQuote`] with the 1-based column
+    /// of the opening quote if a quote is never closed. Otherwise, see
+    /// [`parse`](ArgumentReader::parse) for details.
+    pub fn parse_str(&self, line: &str) -> Result<Arguments, ArgParseError> {
+        let words = shell::split(line, false)
+            .map_err(|e| ArgParseError::UnterminatedQuote(None, e.line, e.column))?;
+
+        self.clone().parse_cli(words)
+    }
+
     /// Parse the provided arguments as environment variables.
-    fn parse_env<K: AsRef<str>, V: AsRef<str>, I: IntoIterator<Item = (K, V)>>(&mut self, args: I) {
-        let mut env_args: Vec<_> = self
-            .args
-            .iter_mut()
-            .filter(|arg| arg.tag.has_env())
-            .collect();
+    fn parse_env<K: AsRef<OsStr>, V: AsRef<OsStr>, I: IntoIterator<Item = (K, V)>>(
+        &mut self,
+        args: I,
+    ) -> Result<(), ArgParseError> {
+        if let Some(prefix) = &self.env_prefix {
+            for arg in &mut self.args {
+                arg.tag.derive_env(prefix);
+            }
+        }
+
+        if !self.args.iter().any(|arg| arg.tag.has_env()) {
+            return Ok(());
+        }
+
+        let mut files = vec![None; self.args.len()];
+        for (key, val) in args {
+            let Some(key) = key.as_ref().to_str() else {
+                continue;
+            };
+
+            for (arg, file) in self.args.iter_mut().zip(&mut files) {
+                let Some(env) = &arg.tag.env else {
+                    continue;
+                };
+
+                if env == key {
+                    arg.val = Some(vec![arg.check_utf8(val.as_ref())?]);
+                    arg.source = ValueSource::Env(key.to_string());
+                } else if arg.tag.env_file && key.strip_suffix("_FILE") == Some(env) {
+                    *file = Some((key.to_string(), PathBuf::from(val.as_ref())));
+                }
+            }
+        }
 
-        if !env_args.is_empty() {
-            for (key, val) in args {
-                let key_ref = key.as_ref();
-                let val = val.as_ref();
-                if let Some(arg) = env_args
-                    .iter_mut()
-                    .find(|arg| arg.tag.env.as_ref().is_some_and(|env| env == key_ref))
-                {
-                    arg.val = Some(Some(val.to_string()));
+        // The variable itself takes precedence over its `_FILE` counterpart.
+        for (arg, file) in self.args.iter_mut().zip(files) {
+            let Some((key, path)) = file else {
+                continue;
+            };
+
+            if matches!(arg.source, ValueSource::Env(_)) {
+                continue;
+            }
+
+            let mut contents =
+                fs::read_to_string(&path).map_err(|e| ArgParseError::Io(path.clone(), e.kind()))?;
+
+            if contents.ends_with('\n') {
+                contents.pop();
+                if contents.ends_with('\r') {
+                    contents.pop();
                 }
             }
+
+            arg.val = Some(vec![OsString::from(contents)]);
+            arg.source = ValueSource::Env(key);
         }
+
+        Ok(())
     }
 
     /// Parses the provided arguments as if they were from the CLI.
     ///
-    /// If `reset == true`, clears the values of all arguments beforehand.
-    /// You probably want to leave this at `false`, unless you're re-using
-    /// your parser.
-    ///
     /// # Errors
     ///
     /// See [`parse`](ArgumentReader::parse) for details.
-    fn parse_cli<A: AsRef<str>, IA: IntoIterator<Item = A>>(
+    fn parse_cli<A: AsRef<OsStr>, IA: IntoIterator<Item = A>>(
         mut self,
         args: IA,
     ) -> Result<Arguments, ArgParseError> {
-        fn tostring<S: AsRef<str>>(arg: S) -> String {
-            <S as AsRef<str>>::as_ref(&arg).to_string()
+        let mut expanded = Vec::new();
+        for arg in args {
+            if self.arg_files {
+                expand_arg_file(arg.as_ref(), &mut expanded, 0)?;
+            } else {
+                expanded.push(arg.as_ref().to_os_string());
+            }
         }
 
-        let mut args = args.into_iter().peekable();
+        let merge_policy = self.merge_policy;
+        let mut args = expanded.into_iter().enumerate();
         let mut remainder = Vec::new();
+        let mut remainder_os = Vec::new();
 
-        while let Some(arg) = args.next() {
-            let arg = arg.as_ref();
-            if let Some(mut long) = arg.strip_prefix("--") {
-                let val = if let Some((left, right)) = long.split_once('=') {
-                    long = left;
-                    Some(right)
-                } else {
-                    None
-                };
-
-                let arg = self
-                    .args
-                    .iter_mut()
-                    .find(|arg| arg.tag.matches_long(long))
-                    .ok_or(ArgParseError::UnknownFlag(long.to_string()))?;
+        while let Some((i, raw)) = args.next() {
+            let raw = raw.as_ref();
+            match Token::classify(raw)? {
+                Token::Long(long, val) => {
+                    #[cfg(feature = "help")]
+                    if let Some(err) = self.builtin_flag(|tag| tag.matches_long(&long)) {
+                        return Err(err);
+                    }
 
-                if !arg.cli_set {
-                    arg.val = None;
-                    arg.cli_set = true;
-                }
+                    let arg = self
+                        .args
+                        .iter_mut()
+                        .find(|arg| arg.tag.matches_long(&long))
+                        .ok_or_else(|| ArgParseError::UnknownFlag(long.clone()))?;
 
-                let val = if arg.consumes {
-                    if val.is_none() {
-                        args.next().map(tostring)
-                    } else {
-                        val.map(tostring)
-                    }
-                } else {
-                    None
-                };
+                    // arguments that don't consume a value still take an
+                    // explicit `--flag=value`, e.g. `--color=no`
+                    let val = match val {
+                        Some(val) => Some(val),
+                        None if arg.consumes => Some(
+                            args.next()
+                                .map(|(_, next)| next)
+                                .ok_or(ArgParseError::MissingValue(long))?,
+                        ),
+                        None => None,
+                    };
 
-                if arg.repeatable {
-                    if let Some(val) = val {
-                        match &mut arg.val {
-                            Some(Some(existing)) => {
-                                existing.push(',');
-                                existing.push_str(&val);
-                            }
-                            _ => {
-                                arg.val = Some(Some(val));
-                            }
-                        }
-                    }
-                } else {
-                    arg.val = Some(val);
+                    let val = val.map(|val| arg.check_utf8(&val)).transpose()?;
+                    arg.set_cli(i, val, merge_policy);
                 }
-            } else if let Some(shorts) = arg.strip_prefix('-') {
-                if shorts.is_empty() {
-                    remainder.push(String::from("-"));
-                } else {
+                Token::Shorts(shorts) => {
                     let mut consumed = false;
                     for short in shorts.chars() {
+                        #[cfg(feature = "help")]
+                        if let Some(err) = self.builtin_flag(|tag| tag.matches_short(short)) {
+                            return Err(err);
+                        }
+
                         let arg = self
                             .args
                             .iter_mut()
@@ -425,46 +1209,113 @@ impl ArgumentReader {
                             .ok_or(ArgParseError::UnknownFlag(short.to_string()))?;
 
                         if arg.consumes && consumed {
-                            return Err(ArgParseError::ConsumedValue(shorts.to_string()));
+                            return Err(ArgParseError::ConsumedValue(shorts));
                         }
 
-                        let next = if arg.consumes {
-                            consumed = true;
-                            args.next().map(|arg| arg.as_ref().to_string())
-                        } else {
-                            None
-                        };
-
-                        if !arg.cli_set {
-                            arg.val = None;
-                            arg.cli_set = true;
-                        }
+                        let next =
+                            if arg.consumes {
+                                consumed = true;
+                                Some(args.next().map(|(_, next)| next).ok_or_else(|| {
+                                    ArgParseError::MissingValue(short.to_string())
+                                })?)
+                            } else {
+                                None
+                            };
 
-                        if arg.repeatable {
-                            if let Some(next) = next {
-                                match &mut arg.val {
-                                    Some(Some(existing)) => {
-                                        existing.push(',');
-                                        existing.push_str(&next);
-                                    }
-                                    _ => {
-                                        arg.val = Some(Some(next));
-                                    }
-                                }
-                            }
-                        } else {
-                            arg.val = Some(next);
-                        }
+                        let next = next.map(|next| arg.check_utf8(&next)).transpose()?;
+                        arg.set_cli(i, next, merge_policy);
                     }
                 }
-            } else {
-                remainder.push(arg.to_string());
+                Token::Positional => {
+                    remainder.push(raw.to_string_lossy().into_owned());
+                    remainder_os.push(raw.to_os_string());
+                }
             }
         }
 
         Ok(Arguments {
             args: self.args,
             remainder,
+            remainder_os,
         })
     }
 }
+
+/// The maximum depth of nested `@path` argument files.
+const MAX_ARG_FILE_DEPTH: usize = 16;
+
+/// Pushes `arg` to `out`, or, if it's an `@path`, the arguments in that file.
+fn expand_arg_file(
+    arg: &OsStr,
+    out: &mut Vec<OsString>,
+    depth: usize,
+) -> Result<(), ArgParseError> {
+    let path = match types::split_once_os(arg, b'@') {
+        Some((before, path)) if before.is_empty() && !path.is_empty() => Path::new(path),
+        _ => {
+            out.push(arg.to_os_string());
+            return Ok(());
+        }
+    };
+
+    if depth >= MAX_ARG_FILE_DEPTH {
+        return Err(ArgParseError::ArgFileDepth(path.to_path_buf()));
+    }
+
+    let src =
+        fs::read_to_string(path).map_err(|e| ArgParseError::Io(path.to_path_buf(), e.kind()))?;
+    let words = shell::split(&src, true).map_err(|e| {
+        ArgParseError::UnterminatedQuote(Some(path.to_path_buf()), e.line, e.column)
+    })?;
+
+    for word in words {
+        expand_arg_file(OsStr::new(&word), out, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+/// A single CLI argument, broken down into its parts.
+enum Token {
+    /// `--long` or `--long=value`.
+    Long(String, Option<OsString>),
+    /// `-abc`.
+    Shorts(String),
+    /// Anything else, including a lone `-`.
+    Positional,
+}
+
+impl Token {
+    fn classify(arg: &OsStr) -> Result<Self, ArgParseError> {
+        let invalid = || ArgParseError::InvalidUnicode(arg.to_string_lossy().into_owned());
+
+        if let Some(arg) = arg.to_str() {
+            return Ok(if let Some(long) = arg.strip_prefix("--") {
+                if let Some((long, val)) = long.split_once('=') {
+                    Self::Long(long.to_string(), Some(OsString::from(val)))
+                } else {
+                    Self::Long(long.to_string(), None)
+                }
+            } else if let Some(shorts) = arg.strip_prefix('-').filter(|s| !s.is_empty()) {
+                Self::Shorts(shorts.to_string())
+            } else {
+                Self::Positional
+            });
+        }
+
+        // Flag names must be valid UTF-8, but a value attached via
+        // `--long=value` needn't be.
+        if let Some((name, val)) = types::split_once_os(arg, b'=') {
+            if let Some(long) = name.to_str().and_then(|name| name.strip_prefix("--")) {
+                return Ok(Self::Long(long.to_string(), Some(val.to_os_string())));
+            }
+        }
+
+        let bytes = arg.as_encoded_bytes();
+        if bytes.starts_with(b"-") && bytes.len() > 1 {
+            Err(invalid())
+        } else {
+            Ok(Self::Positional)
+        }
+    }
+}
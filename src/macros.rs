@@ -2,6 +2,67 @@
 
 pub mod const_exprs;
 
+#[cfg(feature = "help")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __if_help {
+    ( $( $tt:tt )* ) => { $( $tt )* };
+}
+
+#[cfg(not(feature = "help"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __if_help {
+    ( $( $tt:tt )* ) => {};
+}
+
+#[cfg(feature = "completions")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __if_completions {
+    ( $( $tt:tt )* ) => { $( $tt )* };
+}
+
+#[cfg(not(feature = "completions"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __if_completions {
+    ( $( $tt:tt )* ) => {};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __arg_required {
+    // unwrapped and without a default, so parsing panics if it's missing
+    // (unless the type has a default of its own)
+    ( $typ:ty, [], [] ) => {
+        <$typ as $crate::ArgumentType>::default_value().is_none()
+    };
+
+    ( $typ:ty, [ $( $spec:ident )? ], [ $( $default:expr )? ] ) => {
+        false
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __default_text {
+    ( $typ:ty, $default:literal ) => {
+        $crate::__default_text!(@render $typ, $crate::__sarge_default::<$typ, _>($default), $default)
+    };
+
+    ( $typ:ty, $default:expr ) => {
+        $crate::__default_text!(@render $typ, $crate::__sarge_default_expr::<$typ>($default), $default)
+    };
+
+    ( @render $typ:ty, $value:expr, $default:expr ) => {{
+        #[allow(unused_imports)]
+        use $crate::{__SargeDebugText as _, __SargeDisplayText as _, __SargeSourceText as _};
+        let value: $typ = $value;
+        (&&&$crate::__SargeDefaultText(&value, ::std::stringify!($default))).__sarge_text()
+    }};
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __parse_arg {
@@ -94,60 +155,68 @@ macro_rules! __arg_typ {
 macro_rules! __var_tag {
     ( $long:ident $( $doc:literal )* ) => {{
         let tag = $crate::tag::long($crate::__replace!(::std::stringify!($long), '_', '-'));
-        #[cfg(feature = "help")]
-        let tag = {
-            let mut s = ::std::string::String::new();
-            $(
-                s.push_str($doc);
-                s.push('\n');
-            )*
-            s.pop();
-            tag.doc(s)
-        };
+        $crate::__if_help! {
+            let tag = {
+                let mut s = ::std::string::String::new();
+                $(
+                    // `/// doc` comments keep the space after the slashes
+                    s.push_str($doc.strip_prefix(' ').unwrap_or($doc));
+                    s.push('\n');
+                )*
+                s.pop();
+                tag.doc(s)
+            };
+        }
         tag
     }};
     ( $short:literal $long:ident $( $doc:literal )* ) => {{
         let tag = $crate::tag::both($short, $crate::__replace!(::std::stringify!($long), '_', '-'));
-        #[cfg(feature = "help")]
-        let tag = {
-            let mut s = ::std::string::String::new();
-            $(
-                s.push_str($doc);
-                s.push('\n');
-            )*
-            s.pop();
-            tag.doc(s)
-        };
+        $crate::__if_help! {
+            let tag = {
+                let mut s = ::std::string::String::new();
+                $(
+                    // `/// doc` comments keep the space after the slashes
+                    s.push_str($doc.strip_prefix(' ').unwrap_or($doc));
+                    s.push('\n');
+                )*
+                s.pop();
+                tag.doc(s)
+            };
+        }
         tag
     }};
     ( $long:ident $env:ident $( $doc:literal )* ) => {{
         let tag = $crate::tag::long($crate::__replace!(::std::stringify!($long), '_', '-'))
             .env(::std::stringify!($env));
-        #[cfg(feature = "help")]
-        let tag = {
-            let mut s = ::std::string::String::new();
-            $(
-                s.push_str($doc);
-                s.push('\n');
-            )*
-            s.pop();
-            tag.doc(s)
-        };
+        $crate::__if_help! {
+            let tag = {
+                let mut s = ::std::string::String::new();
+                $(
+                    // `/// doc` comments keep the space after the slashes
+                    s.push_str($doc.strip_prefix(' ').unwrap_or($doc));
+                    s.push('\n');
+                )*
+                s.pop();
+                tag.doc(s)
+            };
+        }
         tag
     }};
     ( $short:literal $long:ident $env:ident $( $doc:literal )* ) => {{
         let tag = $crate::tag::both($short, $crate::__replace!(::std::stringify!($long), '_', '-'))
             .env(::std::stringify!($env));
-        #[cfg(feature = "help")]
-        let tag = {
-            let mut s = ::std::string::String::new();
-            $(
-                s.push_str($doc);
-                s.push('\n');
-            )*
-            s.pop();
-            tag.doc(s)
-        };
+        $crate::__if_help! {
+            let tag = {
+                let mut s = ::std::string::String::new();
+                $(
+                    // `/// doc` comments keep the space after the slashes
+                    s.push_str($doc.strip_prefix(' ').unwrap_or($doc));
+                    s.push('\n');
+                )*
+                s.pop();
+                tag.doc(s)
+            };
+        }
         tag
     }};
 }
@@ -203,8 +272,10 @@ macro_rules! __var_tag {
 ///
 /// No wrapper means that if the argument wasn't passed, or failed to parse,
 /// trying to parse your arguments will panic. It gives basic error messages,
-/// but this should still be avoided if possible. It is, however, safe to use
-/// this marker on `bool` arguments, since they will default to `false`.
+/// but this should still be avoided if possible. It is, however, mostly safe
+/// to use this marker on `bool` arguments, since they will default to `false`;
+/// only an unrecognized value (e.g. `--flag=maybe` or `FLAG=maybe`) will
+/// panic. `--flag=no` and the like set them to `false`.
 ///
 /// # Short forms
 ///
@@ -253,6 +324,61 @@ macro_rules! __var_tag {
 /// - `String`: `"text"` (no `.to_string()` / `.into()` needed)
 /// - `Vec<String>`: `vec!["a", "b"]` (elements are converted to `String`)
 ///
+/// On feature `help`, defaults are shown in `help()` using their `Display`
+/// implementation, e.g. `[default: foo]` for `"foo"`, or else `Debug`, e.g.
+/// `[default: ["a", "b"]]`. Types with neither are shown as written.
+///
+/// # Options
+///
+/// Settings for the underlying [`ArgumentReader`](crate::ArgumentReader)
+/// may be given in a `#[sarge(...)]` attribute before the struct name. Each
+/// `option = value` calls the method of the same name, like so:
+///
+/// ```plain
+/// sarge! {
+///     #[sarge(env_prefix = "MYAPP_", arg_files = true)]
+///     Args,
+///     ...
+/// }
+/// ```
+///
+/// For example, `auto_help = true` adds a `-h`/`--help` flag, which makes
+/// parsing return [`ArgParseError::HelpRequested`](crate::ArgParseError::HelpRequested)
+/// with the rendered help, `color = sarge::ColorChoice::Auto` styles that
+/// help when stdout is a terminal, and `dynamic_completion = true` lets
+/// `parse` answer `SARGE_COMPLETE` requests from completion scripts.
+///
+/// # Generated methods
+///
+/// Besides the `parse*` methods, the struct gets `help`, `print_help`,
+/// `manpage` and `schema` on feature `help`, and `completions` and
+/// `print_completions` on feature `completions`. These follow sarge's
+/// features, not your crate's:
+///
+/// ```
+/// # use sarge::prelude::*;
+/// sarge! {
+///     /// Fetches things.
+///     #[sarge(auto_help = true, help_width = 80)]
+///     Args,
+///
+///     /// Don't print progress
+///     'q' quiet: bool,
+/// }
+///
+/// # #[cfg(feature = "completions")] {
+/// assert!(Args::help().contains(" -q --quiet : Don't print progress"));
+/// assert!(Args::manpage().contains("Don't print progress"));
+/// assert_eq!(Args::schema().arguments[0].doc.as_deref(), Some("Don't print progress"));
+/// assert!(Args::completions(sarge::Shell::Fish).contains("-l quiet"));
+///
+/// match Args::parse_cli(["fetch", "--help"]) {
+///     Err(ArgParseError::HelpRequested(help)) => assert!(help.contains("Don't print progress")),
+///     _ => unreachable!(),
+/// }
+/// # }
+/// ```
+///
 /// # Example
 ///
 /// ```
@@ -268,8 +394,8 @@ macro_rules! __var_tag {
 ///     //
 ///     // The hashtags denote the arg 'wrapper'. No wrapper means it will be
 ///     // unwrapped; if the argument wasn't passed, or it failed to parse, this
-///     // will panic. Thankfully, `bool` arguments are immune to both, and
-///     // `String` arguments are immune to the latter.
+///     // will panic. Thankfully, `bool` arguments can't be missing, and
+///     // `String` arguments can't fail to parse.
 ///
 ///     /// Hello, World!
 ///     first: bool, // true if `--first` is passed, false otherwise
@@ -280,7 +406,7 @@ macro_rules! __var_tag {
 ///
 ///     // You can also specify an environment variable counterpart. If an argument
 ///     // has values for both an environment variable and a CLI argument, the CLI
-///     // argument takes precedence.
+///     // argument takes precedence (unless configured otherwise; see `MergePolicy`).
 ///     @ENV_VAR env_var: i32,
 ///
 ///     // `#err` makes the argument an `Option<Result<T, _>>`.
@@ -334,6 +460,7 @@ macro_rules! sarge {
         @__struct
         [ $( $doc:literal )* ]
         [ $( $struct_meta:meta )* ]
+        [ $( $opt:ident = $opt_val:expr, )* ]
         $v:vis $name:ident, $(
             $( #[doc = $field_doc:literal] )*
             $( # $spec:ident )?
@@ -353,17 +480,71 @@ macro_rules! sarge {
         }
 
         impl $name {
-            /// Returns help for all the arguments.
-            ///
-            /// Only available on feature `help`.
-            #[cfg(feature = "help")]
-            #[allow(unused)]
-            pub fn help() -> ::std::string::String {
+            $crate::__if_help! {
+                /// Returns help for all the arguments.
+                ///
+                /// Only available on feature `help`.
+                #[allow(unused)]
+                pub fn help() -> ::std::string::String {
+                    Self::__sarge_reader().0.help()
+                }
+
+                /// Prints help for all the arguments.
+                ///
+                /// Only available on feature `help`.
+                #[allow(unused)]
+                pub fn print_help() {
+                    print!("{}", Self::help());
+                }
+
+                /// Returns a man page (in roff format) for the program.
+                ///
+                /// Only available on feature `help`.
+                #[allow(unused)]
+                pub fn manpage() -> ::std::string::String {
+                    Self::__sarge_reader().0.manpage()
+                }
+
+                /// Returns a description of the program and all of its
+                /// arguments, which can be exported as Markdown or JSON.
+                ///
+                /// Only available on feature `help`.
+                #[allow(unused)]
+                pub fn schema() -> $crate::Schema {
+                    Self::__sarge_reader().0.schema()
+                }
+            }
+
+            $crate::__if_completions! {
+                /// Returns a completion script for the given shell.
+                ///
+                /// Only available on feature `completions`.
+                #[allow(unused)]
+                pub fn completions(shell: $crate::Shell) -> ::std::string::String {
+                    Self::__sarge_reader().0.completions(shell)
+                }
+
+                /// Prints a completion script for the given shell.
+                ///
+                /// Only available on feature `completions`.
+                #[allow(unused)]
+                pub fn print_completions(shell: $crate::Shell) {
+                    print!("{}", Self::completions(shell));
+                }
+            }
+
+            #[doc(hidden)]
+            #[allow(unused, clippy::type_complexity)]
+            fn __sarge_reader() -> (
+                $crate::ArgumentReader,
+                ( $( $crate::ArgumentRef<$typ>, )* ),
+            ) {
                 let mut parser = $crate::ArgumentReader::new();
+                $( parser.$opt($opt_val); )*
 
                 let mut doc = ::std::string::String::new();
                 $(
-                    doc.push_str($doc);
+                    doc.push_str($doc.strip_prefix(' ').unwrap_or($doc));
                     doc.push('\n');
                 )*
                 doc.pop();
@@ -371,25 +552,18 @@ macro_rules! sarge {
                     parser.doc = Some(doc);
                 }
 
-                $(
+                let refs = ($(
                     parser.add::<$typ>(
                         $crate::__var_tag!($( $short )? $long $( $env )? $( $field_doc )*)
-                    );
-                )*
-
-                parser.help()
-            }
+                            $( .default_value($crate::__default_text!($typ, $default)) )?
+                            .required($crate::__arg_required!($typ, [$( $spec )?], [$( $default )?]))
+                    ),
+                )*);
 
-            /// Prints help for all the arguments.
-            ///
-            /// Only available on feature `help`.
-            #[cfg(feature = "help")]
-            #[allow(unused)]
-            pub fn print_help() {
-                print!("{}", Self::help());
+                (parser, refs)
             }
 
-            /// Parse arguments from `std::env::{args,vars}`.
+            /// Parse arguments from `std::env::{args_os,vars_os}`.
             ///
             /// # Errors
             ///
@@ -398,19 +572,12 @@ macro_rules! sarge {
             /// [`ArgParseError`] for a list of all possible errors.
             #[allow(unused)]
             pub fn parse() -> std::result::Result<(Self, std::vec::Vec<std::string::String>), $crate::ArgParseError> {
-
-                Self::parse_provided(
-                    std::env::args(),
-                    std::env::vars(),
-                )
+                let (me, args) = Self::__sarge_parse_with(|parser| parser.parse())?;
+                ::std::result::Result::Ok((me, args.into()))
             }
 
             /// Parse the provided arguments as if they were environment variables.
             ///
-            /// If `reset == true`, clears the values of all arguments beforehand.
-            /// You probably want to leave this at `false`, unless you're re-using
-            /// your parser.
-            ///
             /// # Errors
             ///
             /// See [`parse`] for details.
@@ -428,10 +595,6 @@ macro_rules! sarge {
 
             /// Parses the provided arguments as if they were from the CLI.
             ///
-            /// If `reset == true`, clears the values of all arguments beforehand.
-            /// You probably want to leave this at `false`, unless you're re-using
-            /// your parser.
-            ///
             /// # Errors
             ///
             /// See [`parse`] for details.
@@ -466,15 +629,45 @@ macro_rules! sarge {
                     (Self, std::vec::Vec<std::string::String>), $crate::ArgParseError
                 >
             {
-                let mut parser = $crate::ArgumentReader::new();
+                let (me, args) =
+                    Self::__sarge_parse_with(|parser| parser.parse_provided(cli, env))?;
+                ::std::result::Result::Ok((me, args.into()))
+            }
 
-                $(
-                    let $long = parser.add::<$typ>(
-                        $crate::__var_tag!($( $short )? $long $( $env )? )
-                    );
-                )*
+            /// Parse from the provided environment variables and CLI arguments,
+            /// which may not be valid UTF-8. The remainder is returned exactly
+            /// as it was given.
+            ///
+            /// # Errors
+            ///
+            /// See [`parse`] for details.
+            #[allow(unused)]
+            pub fn parse_provided_os<
+                A: std::convert::AsRef<std::ffi::OsStr>,
+                IA: std::iter::IntoIterator<Item = A>,
+                K: std::convert::AsRef<std::ffi::OsStr>,
+                V: std::convert::AsRef<std::ffi::OsStr>,
+                IE: std::iter::IntoIterator<Item = (K, V)>,
+            >(
+                cli: IA,
+                env: IE,
+            ) -> std::result::Result<
+                    (Self, std::vec::Vec<std::ffi::OsString>), $crate::ArgParseError
+                >
+            {
+                let (me, args) =
+                    Self::__sarge_parse_with(|parser| parser.parse_provided_os(cli, env))?;
+                ::std::result::Result::Ok((me, args.into()))
+            }
 
-                let args = parser.parse_provided(cli, env)?;
+            #[doc(hidden)]
+            fn __sarge_parse_with(
+                parse: impl ::std::ops::FnOnce(
+                    $crate::ArgumentReader,
+                ) -> ::std::result::Result<$crate::Arguments, $crate::ArgParseError>,
+            ) -> ::std::result::Result<(Self, $crate::Arguments), $crate::ArgParseError> {
+                let (parser, ( $( $long, )* )) = Self::__sarge_reader();
+                let args = parse(parser)?;
 
                 $(
                     $crate::__parse_arg!($( $spec )? => args, $long, $typ, $( $default )?);
@@ -484,7 +677,7 @@ macro_rules! sarge {
                     $long,
                 )*};
 
-                ::std::result::Result::Ok((me, args.into()))
+                ::std::result::Result::Ok((me, args))
             }
         }
     };
@@ -493,6 +686,7 @@ macro_rules! sarge {
         @__collect
         [ $( $doc:literal )* ]
         [ $( $struct_meta:meta )* ]
+        [ $( $opt:ident = $opt_val:expr, )* ]
         #[doc = $next_doc:literal]
         $($rest:tt)*
     ) => {
@@ -500,6 +694,7 @@ macro_rules! sarge {
             @__collect
             [ $( $doc )* $next_doc ]
             [ $( $struct_meta )* doc = $next_doc ]
+            [ $( $opt = $opt_val, )* ]
             $($rest)*
         }
     };
@@ -508,6 +703,24 @@ macro_rules! sarge {
         @__collect
         [ $( $doc:literal )* ]
         [ $( $struct_meta:meta )* ]
+        [ $( $opt:ident = $opt_val:expr, )* ]
+        #[sarge( $( $next_opt:ident = $next_val:expr ),* $(,)? )]
+        $($rest:tt)*
+    ) => {
+        $crate::sarge! {
+            @__collect
+            [ $( $doc )* ]
+            [ $( $struct_meta )* ]
+            [ $( $opt = $opt_val, )* $( $next_opt = $next_val, )* ]
+            $($rest)*
+        }
+    };
+
+    (
+        @__collect
+        [ $( $doc:literal )* ]
+        [ $( $struct_meta:meta )* ]
+        [ $( $opt:ident = $opt_val:expr, )* ]
         #[$next_meta:meta]
         $($rest:tt)*
     ) => {
@@ -515,6 +728,7 @@ macro_rules! sarge {
             @__collect
             [ $( $doc )* ]
             [ $( $struct_meta )* $next_meta ]
+            [ $( $opt = $opt_val, )* ]
             $($rest)*
         }
     };
@@ -523,12 +737,14 @@ macro_rules! sarge {
         @__collect
         [ $( $doc:literal )* ]
         [ $( $struct_meta:meta )* ]
+        [ $( $opt:ident = $opt_val:expr, )* ]
         $v:vis $name:ident, $($rest:tt)*
     ) => {
         $crate::sarge! {
             @__struct
             [ $( $doc )* ]
             [ $( $struct_meta )* ]
+            [ $( $opt = $opt_val, )* ]
             $v $name, $($rest)*
         }
     };
@@ -540,6 +756,7 @@ macro_rules! sarge {
             @__collect
             []
             []
+            []
             $( $tt )*
         }
     };
@@ -0,0 +1,227 @@
+//! A description of the CLI surface, for exporting references.
+
+use std::fmt::Write;
+
+use crate::tag::{Cli, Full};
+
+/// A description of every argument an [`ArgumentReader`](crate::ArgumentReader)
+/// accepts. Create with [`ArgumentReader::schema`](crate::ArgumentReader::schema),
+/// then render with [`to_markdown`](Schema::to_markdown) or
+/// [`to_json`](Schema::to_json), or walk it to generate other formats.
+///
+/// Only available on feature `help`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Schema {
+    /// The name of the program.
+    pub name: String,
+    /// Program-level documentation.
+    pub doc: Option<String>,
+    /// Every argument, in the order they were added.
+    pub arguments: Vec<ArgumentSchema>,
+}
+
+/// A description of a single argument. See [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+#[allow(clippy::module_name_repetitions, clippy::struct_excessive_bools)]
+pub struct ArgumentSchema {
+    /// The short form, e.g. `f` for `-f`.
+    pub short: Option<char>,
+    /// The long form, e.g. `foo` for `--foo`.
+    pub long: Option<String>,
+    /// The environment variable, e.g. `FOO`.
+    pub env: Option<String>,
+    /// Whether `{env}_FILE` is also read; see [`Full::env_file`].
+    pub env_file: bool,
+    /// The name of the value in help messages, e.g. `N` or `PATH`, if the
+    /// argument takes one; see [`ArgumentType::VALUE_NAME`](crate::ArgumentType::VALUE_NAME)
+    /// and [`Full::value_name`]. Unlike Rust type names, this is stable
+    /// across compiler versions.
+    pub value_name: Option<String>,
+    /// Whether the argument takes a value, rather than being a flag.
+    pub takes_value: bool,
+    /// Whether the argument may be given multiple times.
+    pub repeatable: bool,
+    /// The documented default value; see [`Full::default_value`].
+    pub default: Option<String>,
+    /// Whether the argument must be given; see [`Full::required`].
+    pub required: bool,
+    /// The documentation for the argument.
+    pub doc: Option<String>,
+}
+
+impl ArgumentSchema {
+    pub(crate) fn new(
+        tag: &Full,
+        value_name: Option<&str>,
+        takes_value: bool,
+        repeatable: bool,
+    ) -> Self {
+        let (short, long) = match &tag.cli {
+            Some(Cli::Short(s)) => (Some(*s), None),
+            Some(Cli::Long(l)) => (None, Some(l.clone())),
+            Some(Cli::Both(s, l)) => (Some(*s), Some(l.clone())),
+            None => (None, None),
+        };
+
+        Self {
+            short,
+            long,
+            env: tag.env.clone(),
+            env_file: tag.env_file,
+            value_name: value_name.map(String::from),
+            takes_value,
+            repeatable,
+            default: tag.default_value.clone(),
+            required: tag.required,
+            doc: tag.doc.clone(),
+        }
+    }
+
+    /// Every CLI form of the argument, e.g. `["-f", "--foo"]`.
+    pub fn flags(&self) -> Vec<String> {
+        let short = self.short.map(|s| format!("-{s}"));
+        let long = self.long.as_ref().map(|l| format!("--{l}"));
+        short.into_iter().chain(long).collect()
+    }
+}
+
+/// Escapes text for a Markdown table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .lines()
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+/// Renders a string as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Renders an optional string as a JSON string literal or `null`.
+fn json_option(text: Option<&str>) -> String {
+    text.map_or_else(|| "null".to_string(), json_string)
+}
+
+impl Schema {
+    /// Renders the schema as a Markdown reference, with the program's docs
+    /// followed by a table of arguments.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.name);
+
+        if let Some(doc) = &self.doc {
+            out.push_str(doc);
+            out.push_str("\n\n");
+        }
+
+        if self.arguments.is_empty() {
+            return out;
+        }
+
+        out.push_str("## Arguments\n\n");
+        out.push_str("| Flags | Environment | Value | Default | Description |\n");
+        out.push_str("| --- | --- | --- | --- | --- |\n");
+
+        for arg in &self.arguments {
+            let flags = arg
+                .flags()
+                .iter()
+                .map(|flag| format!("`{flag}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let env = arg
+                .env
+                .as_ref()
+                .map(|env| format!("`{env}`"))
+                .unwrap_or_default();
+
+            let value = arg
+                .value_name
+                .as_ref()
+                .map(|value| format!("`{}`", markdown_cell(value)))
+                .unwrap_or_default();
+
+            let default = arg
+                .default
+                .as_ref()
+                .map(|default| format!("`{}`", markdown_cell(default)))
+                .unwrap_or_default();
+
+            let mut doc = arg.doc.as_deref().map(markdown_cell).unwrap_or_default();
+            if arg.required {
+                if !doc.is_empty() {
+                    doc.push(' ');
+                }
+                doc.push_str("**(required)**");
+            }
+
+            let _ = writeln!(out, "| {flags} | {env} | {value} | {default} | {doc} |");
+        }
+
+        out
+    }
+
+    /// Renders the schema as pretty-printed JSON, e.g. for diffing the CLI
+    /// surface between releases.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n");
+        let _ = writeln!(out, "  \"name\": {},", json_string(&self.name));
+        let _ = writeln!(out, "  \"doc\": {},", json_option(self.doc.as_deref()));
+
+        if self.arguments.is_empty() {
+            out.push_str("  \"arguments\": []\n}\n");
+            return out;
+        }
+
+        out.push_str("  \"arguments\": [\n");
+        for (i, arg) in self.arguments.iter().enumerate() {
+            out.push_str("    {\n");
+            let short = arg.short.map(|s| s.to_string());
+            let _ = writeln!(out, "      \"short\": {},", json_option(short.as_deref()));
+            let _ = writeln!(out, "      \"long\": {},", json_option(arg.long.as_deref()));
+            let _ = writeln!(out, "      \"env\": {},", json_option(arg.env.as_deref()));
+            let _ = writeln!(out, "      \"env_file\": {},", arg.env_file);
+            let _ = writeln!(
+                out,
+                "      \"value_name\": {},",
+                json_option(arg.value_name.as_deref())
+            );
+            let _ = writeln!(out, "      \"takes_value\": {},", arg.takes_value);
+            let _ = writeln!(out, "      \"repeatable\": {},", arg.repeatable);
+            let _ = writeln!(
+                out,
+                "      \"default\": {},",
+                json_option(arg.default.as_deref())
+            );
+            let _ = writeln!(out, "      \"required\": {},", arg.required);
+            let _ = writeln!(out, "      \"doc\": {}", json_option(arg.doc.as_deref()));
+            out.push_str(if i + 1 == self.arguments.len() {
+                "    }\n"
+            } else {
+                "    },\n"
+            });
+        }
+        out.push_str("  ]\n}\n");
+
+        out
+    }
+}
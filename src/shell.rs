@@ -0,0 +1,97 @@
+//! POSIX-shell-like splitting of a string into words.
+
+/// An unterminated quote (or trailing backslash) while splitting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Unterminated {
+    /// The 1-based line of the opening quote.
+    pub line: usize,
+    /// The 1-based column (in characters) of the opening quote.
+    pub column: usize,
+}
+
+/// Splits `src` into words, like a POSIX shell would (minus any expansions).
+///
+/// Words are separated by unquoted whitespace. Single quotes preserve
+/// everything literally; double quotes preserve everything except `\"`,
+/// `\\`, `\$`, `` \` ``, and escaped newlines; outside of quotes, a backslash
+/// escapes any character. If `comments` is set, an unquoted `#` at the start
+/// of a word begins a comment that runs to the end of the line.
+pub(crate) fn split(src: &str, comments: bool) -> Result<Vec<String>, Unterminated> {
+    let position = |offset: usize| {
+        let before = &src[..offset];
+        Unterminated {
+            line: before.matches('\n').count() + 1,
+            column: before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1,
+        }
+    };
+
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+
+    let mut chars = src.char_indices();
+    while let Some((start, ch)) = chars.next() {
+        match ch {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, ch)) => word.push(ch),
+                        None => return Err(position(start)),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '\n')) => {}
+                            Some((_, ch @ ('"' | '\\' | '$' | '`'))) => word.push(ch),
+                            Some((_, ch)) => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                            None => return Err(position(start)),
+                        },
+                        Some((_, ch)) => word.push(ch),
+                        None => return Err(position(start)),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, ch)) => {
+                    in_word = true;
+                    word.push(ch);
+                }
+                None => return Err(position(start)),
+            },
+            '#' if comments && !in_word => {
+                for (_, ch) in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            ch if ch.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            ch => {
+                in_word = true;
+                word.push(ch);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
@@ -0,0 +1,58 @@
+use std::fmt::Display;
+
+/// Where the value of an argument came from. Retrieve with
+/// [`ArgumentRef::source`](crate::ArgumentRef::source).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValueSource {
+    /// The argument was given on the CLI. Holds the index of the (last) flag
+    /// in the provided arguments (after expanding any
+    /// [argument files](crate::ArgumentReader::arg_files)), so `0` is usually
+    /// the executable name.
+    Cli(usize),
+    /// The argument was given via the named environment variable.
+    Env(String),
+    /// The argument was given in a configuration file (see
+    /// [`ArgumentReader::parse_config`](crate::ArgumentReader::parse_config)).
+    /// Holds the 1-based line number of its (last) entry.
+    Config(usize),
+    /// The argument wasn't given, so its value is the default (if any).
+    Default,
+}
+
+impl ValueSource {
+    /// Returns whether or not the value came from the CLI.
+    pub fn is_cli(&self) -> bool {
+        matches!(self, Self::Cli(_))
+    }
+}
+
+impl Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cli(i) => write!(f, "argument #{i}"),
+            Self::Env(name) => write!(f, "${name}"),
+            Self::Config(line) => write!(f, "config line {line}"),
+            Self::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// How a value given on the CLI is merged with one from an environment
+/// variable or configuration file. Set with
+/// [`ArgumentReader::merge_policy`](crate::ArgumentReader::merge_policy) or
+/// [`Full::merge_policy`](crate::tag::Full::merge_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MergePolicy {
+    /// The CLI value replaces the existing value entirely.
+    #[default]
+    Override,
+    /// For repeatable arguments (such as `Vec<T>`), CLI values are appended
+    /// to the existing values. Other arguments behave like
+    /// [`Override`](MergePolicy::Override).
+    Append,
+    /// A value from an environment variable takes precedence over the CLI,
+    /// e.g. so operators can override a deployment's arguments. CLI values
+    /// still replace values from configuration files.
+    EnvWins,
+}
@@ -0,0 +1,98 @@
+//! ANSI styling for help messages and errors.
+
+use std::io::IsTerminal;
+
+/// When to style output with ANSI escape codes. See
+/// [`ArgumentReader::color`](crate::ArgumentReader::color).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Style output if the stream is a terminal, following the `NO_COLOR`
+    /// and `CLICOLOR_FORCE` conventions: if `CLICOLOR_FORCE` is set to
+    /// anything but `0`, always style; otherwise, if `NO_COLOR` is set to
+    /// anything but the empty string, never style.
+    Auto,
+    /// Always style output.
+    Always,
+    /// Never style output. This is the default.
+    #[default]
+    Never,
+}
+
+impl ColorChoice {
+    /// Returns whether output written to `stream` should be styled, e.g.
+    /// `ColorChoice::Auto.should_color(&std::io::stderr())` for errors.
+    pub fn should_color<S: IsTerminal>(self, stream: &S) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                let set = |name| std::env::var_os(name).filter(|val| !val.is_empty());
+                if set("CLICOLOR_FORCE").is_some_and(|val| val != "0") {
+                    true
+                } else if set("NO_COLOR").is_some() {
+                    false
+                } else {
+                    stream.is_terminal()
+                }
+            }
+        }
+    }
+}
+
+/// The styles used for each part of help messages and errors. Each is a
+/// list of SGR parameters, e.g. `"1"` for bold or `"1;36"` for bold cyan;
+/// an empty string leaves that part unstyled.
+///
+/// [`Theme::default`] uses only bold, dim, italic and underline, so it suits
+/// any terminal color scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Theme {
+    /// Flags, e.g. `--foo`, and the names of arguments in errors.
+    pub flag: &'static str,
+    /// Value placeholders, e.g. `<N>`.
+    pub placeholder: &'static str,
+    /// Environment variables, e.g. `$FOO`.
+    pub env: &'static str,
+    /// Section headings, e.g. `Examples:`.
+    pub heading: &'static str,
+    /// The usage line.
+    pub usage: &'static str,
+    /// Error messages.
+    pub error: &'static str,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            flag: "1",
+            placeholder: "3",
+            env: "2",
+            heading: "1;4",
+            usage: "1",
+            error: "",
+        }
+    }
+}
+
+impl Theme {
+    /// A theme that leaves everything unstyled.
+    pub const fn plain() -> Self {
+        Self {
+            flag: "",
+            placeholder: "",
+            env: "",
+            heading: "",
+            usage: "",
+            error: "",
+        }
+    }
+
+    /// Wraps `text` in the escape codes for `style`.
+    pub(crate) fn paint(style: &str, text: &str) -> String {
+        if style.is_empty() || text.is_empty() {
+            text.to_string()
+        } else {
+            format!("\x1b[{style}m{text}\x1b[0m")
+        }
+    }
+}
@@ -2,6 +2,14 @@
 
 use std::{fmt::Display, hash::Hash};
 
+use crate::{Delimiter, MergePolicy};
+
+#[cfg(feature = "completions")]
+use std::sync::Arc;
+
+#[cfg(feature = "completions")]
+use crate::completions::CompleterFn;
+
 /// Create a tag with just a short variant.
 #[inline]
 pub fn short<S: Into<char>>(s: S) -> Full {
@@ -29,9 +37,20 @@ pub fn env<E: Into<String>>(e: E) -> Full {
     Full {
         cli: None,
         env: Some(e.into()),
+        env_file: false,
+        merge_policy: None,
+        delimiter: None,
+        required: false,
+        default_value: None,
+        value_name: None,
 
         #[cfg(feature = "help")]
         doc: None,
+        #[cfg(feature = "help")]
+        heading: None,
+
+        #[cfg(feature = "completions")]
+        completer: None,
     }
 }
 
@@ -43,10 +62,22 @@ pub fn env<E: Into<String>>(e: E) -> Full {
 pub struct Full {
     pub(crate) cli: Option<Cli>,
     pub(crate) env: Option<String>,
+    pub(crate) env_file: bool,
+    pub(crate) merge_policy: Option<MergePolicy>,
+    pub(crate) delimiter: Option<Delimiter>,
+    pub(crate) required: bool,
+    pub(crate) default_value: Option<String>,
+    pub(crate) value_name: Option<String>,
 
     /// The documentation for this argument.
     #[cfg(feature = "help")]
     pub doc: Option<String>,
+
+    #[cfg(feature = "help")]
+    pub(crate) heading: Option<String>,
+
+    #[cfg(feature = "completions")]
+    pub(crate) completer: Option<CompleterFn>,
 }
 
 impl Full {
@@ -65,6 +96,68 @@ impl Full {
         self
     }
 
+    /// Enable or disable reading the value from a file named by
+    /// `{ENV}_FILE` when the environment variable `{ENV}` itself isn't set,
+    /// following the common convention for Docker and Kubernetes secrets
+    /// (e.g. `PASSWORD_FILE=/run/secrets/pw`). A single trailing newline is
+    /// removed from the file's contents.
+    #[must_use]
+    pub fn env_file(mut self, enabled: bool) -> Self {
+        self.env_file = enabled;
+        self
+    }
+
+    /// Set how a value given on the CLI is merged with one from an
+    /// environment variable or configuration file, overriding the reader's
+    /// [`merge_policy`](crate::ArgumentReader::merge_policy).
+    #[must_use]
+    pub fn merge_policy(mut self, policy: MergePolicy) -> Self {
+        self.merge_policy = Some(policy);
+        self
+    }
+
+    /// Set how a single value is split into elements for list arguments,
+    /// such as `Vec<T>`. Defaults to the type's
+    /// [`DELIMITER`](crate::ArgumentType::DELIMITER), which is
+    /// [`Delimiter::Char(',')`](Delimiter::Char) for most types.
+    #[must_use]
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Document whether the argument must be given a value, shown in the
+    /// [schema](crate::ArgumentReader::schema). This is only descriptive:
+    /// parsing doesn't check it. `sarge!` sets it for fields with no wrapper
+    /// and no default (unless the type has a default of its own, like
+    /// `bool`), since those panic when missing.
+    #[must_use]
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Document the value used when the argument isn't given, shown in help
+    /// as `[default: ...]`. This is only descriptive, e.g. for help and
+    /// [`Schema`](crate::Schema) exports;
+    /// [`ArgumentRef::get`](crate::ArgumentRef::get) still returns `None`.
+    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn default_value<S: ToString>(mut self, value: S) -> Self {
+        self.default_value = Some(value.to_string());
+        self
+    }
+
+    /// Set the name of the value in help messages, e.g. `FILE` for
+    /// `--input <FILE>`, overriding
+    /// [`ArgumentType::VALUE_NAME`](crate::ArgumentType::VALUE_NAME).
+    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn value_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.value_name = Some(name.into());
+        self
+    }
+
     /// Add documentation to the argument. If `doc.is_empty()`, instead
     /// removes any documentation.
     ///
@@ -82,6 +175,37 @@ impl Full {
         }
     }
 
+    /// Set a function that supplies candidate values for this argument
+    /// during dynamic completion (see
+    /// [`ArgumentReader::complete`](crate::ArgumentReader::complete)). It's
+    /// given the partial value under the cursor; candidates that don't start
+    /// with it are dropped. It may capture state, e.g. a list loaded at
+    /// startup; see [`Completer`](crate::Completer).
+    ///
+    /// Only available on feature `completions`.
+    #[must_use]
+    #[cfg(feature = "completions")]
+    pub fn completer<F>(mut self, completer: F) -> Self
+    where
+        F: Fn(&str) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.completer = Some(CompleterFn(Arc::new(completer)));
+        self
+    }
+
+    /// Group the argument under a heading in help messages, e.g.
+    /// `"Network options"`. Arguments without a heading are listed first;
+    /// headings appear in the order they're first used.
+    ///
+    /// Only available on feature `help`.
+    #[must_use]
+    #[cfg(feature = "help")]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn heading<S: Into<String>>(mut self, heading: S) -> Self {
+        self.heading = Some(heading.into());
+        self
+    }
+
     /// Returns whether or not this tag has a CLI component.
     pub fn has_cli(&self) -> bool {
         self.cli.is_some()
@@ -112,6 +236,33 @@ impl Full {
             .is_some_and(|tag| tag.matches_short(short))
     }
 
+    /// Returns the long-form name of the CLI component, if any.
+    pub(crate) fn long_name(&self) -> Option<&str> {
+        match self.cli.as_ref()? {
+            Cli::Short(_) => None,
+            Cli::Long(l) | Cli::Both(_, l) => Some(l),
+        }
+    }
+
+    /// If there isn't an environment variable component, derives one from the
+    /// long-form name, e.g. `--log-level` becomes `{prefix}LOG_LEVEL` and
+    /// `--net.port` becomes `{prefix}NET_PORT`.
+    pub(crate) fn derive_env(&mut self, prefix: &str) {
+        if self.env.is_none() {
+            if let Some(long) = self.long_name() {
+                let name = long
+                    .chars()
+                    .map(|ch| match ch {
+                        '-' | '.' => '_',
+                        ch => ch.to_ascii_uppercase(),
+                    })
+                    .collect::<String>();
+
+                self.env = Some(format!("{prefix}{name}"));
+            }
+        }
+    }
+
     /// Returns whether or not the environment variable component matches the
     /// given name.
     pub fn matches_env(&self, env: &str) -> bool {
@@ -124,9 +275,20 @@ impl From<Cli> for Full {
         Self {
             cli: Some(tag),
             env: None,
+            env_file: false,
+            merge_policy: None,
+            delimiter: None,
+            required: false,
+            default_value: None,
+            value_name: None,
 
             #[cfg(feature = "help")]
             doc: None,
+            #[cfg(feature = "help")]
+            heading: None,
+
+            #[cfg(feature = "completions")]
+            completer: None,
         }
     }
 }
@@ -166,9 +328,20 @@ impl Cli {
         Full {
             cli: Some(self),
             env: Some(env),
+            env_file: false,
+            merge_policy: None,
+            delimiter: None,
+            required: false,
+            default_value: None,
+            value_name: None,
 
             #[cfg(feature = "help")]
             doc: None,
+            #[cfg(feature = "help")]
+            heading: None,
+
+            #[cfg(feature = "completions")]
+            completer: None,
         }
     }
 
@@ -1,6 +1,14 @@
 use crate::prelude::*;
+use crate::{MergePolicy, ValueSource};
 
+mod arg_files;
+#[cfg(feature = "completions")]
+mod completions;
+mod config;
 mod custom_type;
+#[cfg(feature = "help")]
+mod schema;
+mod types;
 
 #[cfg(feature = "macros")]
 mod macros;
@@ -23,6 +31,312 @@ fn help_returns_string() {
     assert!(s.contains("Print help"));
 }
 
+#[cfg(feature = "help")]
+#[test]
+fn help_placeholders() {
+    let mut parser = ArgumentReader::new();
+    let _jobs = parser.add::<usize>(tag::both('j', "jobs").default_value(4).doc("Threads"));
+    let _input = parser.add::<std::path::PathBuf>(tag::short('i').env("INPUT"));
+    let _out = parser.add::<String>(tag::long("out").value_name("FILE"));
+    let _quiet = parser.add::<bool>(tag::long("quiet"));
+    parser.help_width(80);
+
+    assert_eq!(
+        parser.help().split_once('\n').unwrap().1,
+        " -j --jobs <N>          : Threads [default: 4]
+ -i <PATH>       $INPUT
+    --out <FILE>
+    --quiet
+"
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_soft_wrap() {
+    let mut parser = ArgumentReader::new();
+    parser.doc = Some("A program with a fairly long description, which wraps".into());
+    let _fast = parser.add::<bool>(
+        tag::both('f', "fast").doc("Go as fast as possible, ignoring every safety check"),
+    );
+    let _name = parser
+        .add::<String>(tag::long("name").doc("名前を入力してください。よろしく nai\u{308}ve café"));
+    parser.help_width(50);
+
+    assert_eq!(
+        parser.help().split_once('\n').unwrap().1,
+        "A program with a fairly long description, which
+wraps
+
+ -f --fast          : Go as fast as possible,
+                      ignoring every safety check
+    --name <STRING> : 名前を入力してください。よろ
+                      しく nai\u{308}ve café
+"
+    );
+
+    // columns too wide for the docs push them onto their own lines
+    let mut parser = ArgumentReader::new();
+    let _long = parser.add::<String>(
+        tag::long("a-really-quite-long-argument-name")
+            .doc("Short docs, wrapped onto the next line"),
+    );
+    parser.help_width(40);
+
+    assert_eq!(
+        parser.help().split_once('\n').unwrap().1,
+        " --a-really-quite-long-argument-name <STRING>
+        Short docs, wrapped onto the
+        next line
+"
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_emoji_width() {
+    use crate::help::display_width;
+
+    // transport, newer symbols, and emoji-presentation dingbats are wide
+    assert_eq!(display_width("🚀🛑"), 4);
+    assert_eq!(display_width("🪐🫠"), 4);
+    assert_eq!(display_width("⚡✅❌"), 6);
+
+    // text-presentation symbols aren't
+    assert_eq!(display_width("☀✓"), 2);
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_explicit_name() {
+    let mut parser = ArgumentReader::new();
+    parser.name("my-tool");
+    let _quiet = parser.add::<bool>(tag::long("quiet"));
+
+    assert!(parser
+        .help()
+        .starts_with("my-tool [options...] <arguments...>\n"));
+    assert!(parser.manpage().starts_with(".TH MY\\-TOOL 1\n"));
+    assert_eq!(parser.schema().name, "my-tool");
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn builtin_help_and_version() {
+    let mut parser = ArgumentReader::new();
+    parser
+        .name("app")
+        .version("2.0.0")
+        .auto_help(true)
+        .auto_version(true)
+        .help_width(80);
+    let _input = parser.add::<String>(tag::long("input"));
+
+    let help = parser.help();
+    assert!(help.contains(" -h --help"));
+    assert!(help.contains(" -V --version"));
+
+    for args in [["app", "--help"], ["app", "-h"]] {
+        assert_eq!(
+            parser.parse_ref(args, None::<(&str, &str)>).unwrap_err(),
+            ArgParseError::HelpRequested(help.clone())
+        );
+    }
+
+    assert_eq!(
+        parser.parse_str("--input x -V").unwrap_err(),
+        ArgParseError::VersionRequested("app 2.0.0\n".into())
+    );
+
+    // arguments take precedence over built-in flags
+    let mut parser = ArgumentReader::new();
+    parser.auto_help(true).auto_version(true);
+    let host = parser.add::<String>(tag::both('h', "host"));
+    let version = parser.add::<bool>(tag::long("version"));
+
+    let args = parser.parse_str("-h localhost --version").unwrap();
+    assert_eq!(host.get(&args), Some(Ok("localhost".to_string())));
+    assert_eq!(version.get(&args), Some(Ok(true)));
+    assert!(matches!(
+        parser.parse_str("--help"),
+        Err(ArgParseError::HelpRequested(_))
+    ));
+    assert!(matches!(
+        parser.parse_str("-V"),
+        Err(ArgParseError::VersionRequested(_))
+    ));
+
+    let help = parser.help();
+    assert!(help.contains("    --help"));
+    assert!(help.contains(" -V\n") || help.contains(" -V "));
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_styled() {
+    use crate::{ColorChoice, Theme};
+
+    let mut parser = ArgumentReader::new();
+    parser
+        .name("app")
+        .usage("<url>")
+        .color(ColorChoice::Always)
+        .help_width(80);
+    let _jobs = parser.add::<usize>(tag::both('j', "jobs").env("JOBS").doc("Threads"));
+    let _quiet = parser.add::<bool>(tag::long("quiet").heading("Output"));
+
+    // styling doesn't affect the alignment
+    assert_eq!(
+        parser.help(),
+        "\x1b[1mapp <url>\x1b[0m
+ \x1b[1m-j\x1b[0m \x1b[1m--jobs\x1b[0m \x1b[3m<N>\x1b[0m \x1b[2m$JOBS\x1b[0m : Threads
+
+\x1b[1;4mOutput:\x1b[0m
+    \x1b[1m--quiet\x1b[0m
+"
+    );
+
+    parser.theme(Theme::plain());
+    assert_eq!(
+        parser.help(),
+        "app <url>
+ -j --jobs <N> $JOBS : Threads
+
+Output:
+    --quiet
+"
+    );
+
+    parser.theme(Theme::default()).color(ColorChoice::Never);
+    assert!(!parser.help().contains('\x1b'));
+    assert!(!ColorChoice::Never.should_color(&std::io::stdout()));
+    assert!(ColorChoice::Always.should_color(&std::io::stdout()));
+}
+
+#[test]
+fn error_styled() {
+    use crate::Theme;
+
+    let theme = Theme {
+        error: "31",
+        ..Theme::default()
+    };
+    assert_eq!(
+        ArgParseError::UnknownFlag("--foo".into()).styled(&theme),
+        "\x1b[31mUnknown flag: \x1b[0m`\x1b[1m--foo\x1b[0m`"
+    );
+    assert_eq!(
+        ArgParseError::MissingValue("-x".into()).styled(&Theme::plain()),
+        "Expected value for `-x`"
+    );
+    assert_eq!(
+        ArgParseError::HelpRequested("`help`\n".into()).styled(&theme),
+        "`help`\n"
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_sections() {
+    let mut parser = ArgumentReader::new();
+    parser
+        .version("1.2.3")
+        .usage("[options...] <url>")
+        .example("fetch --retries 3 https://example.com")
+        .example("fetch -q https://example.com")
+        .see_also("curl(1)")
+        .help_width(80);
+
+    let _quiet = parser.add::<bool>(tag::both('q', "quiet").heading("Output options"));
+    let _retries = parser.add::<u32>(tag::long("retries").heading("Network options"));
+    let _verbose = parser.add::<bool>(tag::both('v', "verbose").heading("Output options"));
+    let _config = parser.add::<String>(tag::long("config"));
+
+    let (name, help) = parser
+        .help()
+        .split_once(' ')
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .unwrap();
+    assert_eq!(
+        help,
+        format!(
+            "1.2.3
+{name} [options...] <url>
+    --config <STRING>
+
+Output options:
+ -q --quiet
+ -v --verbose
+
+Network options:
+    --retries <N>
+
+Examples:
+  fetch --retries 3 https://example.com
+  fetch -q https://example.com
+
+See also:
+  curl(1)
+"
+        )
+    );
+
+    let man = parser.manpage();
+    assert!(man.contains(" \"\" \""));
+    assert!(man.contains(".SH SYNOPSIS\n.B "));
+    assert!(man.contains("[options...] <url>\n.SH OPTIONS\n"));
+    assert!(man.contains(".SS Output options\n"));
+    assert!(man.ends_with(
+        ".SH EXAMPLES
+.PP
+.nf
+fetch \\-\\-retries 3 https://example.com
+.fi
+.PP
+.nf
+fetch \\-q https://example.com
+.fi
+.SH SEE ALSO
+curl(1)
+"
+    ));
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn manpage_sections() {
+    let mut parser = ArgumentReader::new();
+    parser.doc = Some("Does things.\n\n.Really well, with C:\\ paths.".to_string());
+
+    let _name = parser.add::<String>(tag::both('n', "user-name").doc("Name to use"));
+    let _quiet = parser.add::<bool>(tag::long("quiet").env("QUIET"));
+    let _token = parser.add::<String>(tag::env("TOKEN").doc("API token"));
+
+    let s = parser.manpage();
+    assert!(s.starts_with(".TH "));
+    assert!(s.contains(" \\- Does things.\n.SH SYNOPSIS\n"));
+    assert!(s.ends_with(
+        r".SH DESCRIPTION
+Does things.
+.sp
+\&.Really well, with C:\e paths.
+.SH OPTIONS
+.TP
+\fB\-n\fR, \fB\-\-user\-name\fR \fISTRING\fR
+Name to use
+.TP
+\fB\-\-quiet\fR
+.SH ENVIRONMENT
+.TP
+.B QUIET
+Same as \fB\-\-quiet\fR.
+.TP
+.B TOKEN
+API token
+"
+    ));
+}
+
 #[test]
 fn basic_arg_test() {
     let mut parser = ArgumentReader::new();
@@ -100,6 +414,23 @@ fn multiple_short_vals_consume_same_value() {
     parser.parse_cli(args).unwrap();
 }
 
+#[test]
+fn trailing_flag_missing_value() {
+    let mut parser = ArgumentReader::new();
+    let _name = parser.add::<String>(tag::both('n', "name"));
+    let _quiet = parser.add::<bool>(tag::short('q'));
+
+    assert_eq!(
+        parser.parse_str("--name").unwrap_err(),
+        ArgParseError::MissingValue("name".into())
+    );
+    assert_eq!(
+        parser.parse_str("-qn").unwrap_err(),
+        ArgParseError::MissingValue("n".into())
+    );
+    assert!(parser.parse_str("-q").is_ok());
+}
+
 #[test]
 fn list_type() {
     let mut parser = ArgumentReader::new();
@@ -202,3 +533,273 @@ fn many_env_vars() {
     assert_eq!(threads.get(&args), Some(Ok(16u64)));
     assert_eq!(unused.get(&args), None);
 }
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_values() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+
+    let bad = OsStr::from_bytes(b"file\xff.txt");
+
+    let mut parser = ArgumentReader::new();
+    let path = parser.add::<PathBuf>(tag::long("path").env("PATH_ARG"));
+    let name = parser.add::<String>(tag::long("name"));
+
+    let args = parser
+        .clone()
+        .parse_provided_os(
+            [OsStr::new("test"), OsStr::new("--path"), bad, bad],
+            [(OsStr::new("UNRELATED"), bad)],
+        )
+        .expect("failed to parse non-UTF-8 arguments");
+
+    assert_eq!(path.get(&args), Some(Ok(PathBuf::from(bad))));
+    assert_eq!(args.remainder(), &["test", "file\u{fffd}.txt"]);
+    assert_eq!(args.remainder_os(), &[OsStr::new("test"), bad]);
+
+    let mut long = OsStr::new("--path=").to_os_string();
+    long.push(bad);
+    let args = parser
+        .clone()
+        .parse_provided_os([long], [(OsStr::new("PATH_ARG"), OsStr::new("x"))])
+        .expect("failed to parse non-UTF-8 arguments");
+
+    assert_eq!(path.get(&args), Some(Ok(PathBuf::from(bad))));
+    assert_eq!(name.get(&args), None);
+
+    let err = parser
+        .parse_provided_os([OsStr::new("--name"), bad], None::<(&OsStr, &OsStr)>)
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        ArgParseError::InvalidUnicode("file\u{fffd}.txt".to_string())
+    );
+}
+
+#[test]
+fn value_sources() {
+    let mut parser = ArgumentReader::new();
+    let port = parser.add::<u16>(tag::long("port").env("APP_PORT"));
+    let host = parser.add::<String>(tag::long("host").env("APP_HOST"));
+    let list = parser.add::<Vec<u32>>(tag::short('l').env("LIST"));
+    let unused = parser.add::<bool>(tag::short('u'));
+
+    let env = [("APP_PORT", "80"), ("APP_HOST", "env"), ("LIST", "1,2")];
+    let cli = ["test", "--host", "cli", "-l", "3", "-l", "4"];
+
+    let args = parser
+        .parse_provided(cli, env)
+        .expect("failed to parse provided arguments");
+
+    assert_eq!(
+        port.source(&args),
+        &ValueSource::Env("APP_PORT".to_string())
+    );
+    assert_eq!(host.source(&args), &ValueSource::Cli(1));
+    assert_eq!(list.source(&args), &ValueSource::Cli(5));
+    assert_eq!(unused.source(&args), &ValueSource::Default);
+
+    assert_eq!(
+        format!(
+            "port {} (from {})",
+            port.get(&args).unwrap().unwrap(),
+            port.source(&args)
+        ),
+        "port 80 (from $APP_PORT)"
+    );
+}
+
+#[test]
+fn env_prefix() {
+    let mut parser = ArgumentReader::new();
+    let level = parser.add::<String>(tag::long("log-level"));
+    let port = parser.add::<u16>(tag::long("net.port"));
+    let explicit = parser.add::<String>(tag::both('e', "explicit").env("EXPLICIT"));
+    let short = parser.add::<bool>(tag::short('s'));
+    parser.env_prefix("MYAPP_");
+
+    let env = [
+        ("MYAPP_LOG_LEVEL", "debug"),
+        ("MYAPP_NET_PORT", "8080"),
+        ("MYAPP_EXPLICIT", "ignored"),
+        ("EXPLICIT", "used"),
+    ];
+
+    let args = parser
+        .parse_provided(["test"], env)
+        .expect("failed to parse provided arguments");
+
+    assert_eq!(level.get(&args), Some(Ok("debug".to_string())));
+    assert_eq!(level.tag(&args).env.as_deref(), Some("MYAPP_LOG_LEVEL"));
+    assert_eq!(port.get(&args), Some(Ok(8080)));
+    assert_eq!(explicit.get(&args), Some(Ok("used".to_string())));
+    assert_eq!(short.get(&args), Some(Ok(false)));
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn env_prefix_help() {
+    let mut parser = ArgumentReader::new();
+    let _level = parser.add::<String>(tag::long("log-level"));
+    parser.env_prefix("MYAPP_");
+
+    assert!(parser.help().contains("$MYAPP_LOG_LEVEL"));
+}
+
+#[test]
+fn env_file_secrets() {
+    let path = std::env::temp_dir().join(format!("sarge-{}-secret", std::process::id()));
+    std::fs::write(&path, "hunter2\n").unwrap();
+    let path_str = path.to_str().unwrap();
+
+    let mut parser = ArgumentReader::new();
+    let password = parser.add::<String>(tag::env("PASSWORD").env_file(true));
+    let direct = parser.add::<String>(tag::env("DIRECT").env_file(true));
+    let disabled = parser.add::<String>(tag::env("DISABLED"));
+    let missing = parser.add::<String>(tag::env("MISSING").env_file(true));
+
+    let env = [
+        ("PASSWORD_FILE", path_str),
+        ("DIRECT", "direct"),
+        ("DIRECT_FILE", "/nonexistent/secret"),
+        ("DISABLED_FILE", path_str),
+    ];
+
+    let args = parser
+        .clone()
+        .parse_provided(["test"], env)
+        .expect("failed to parse provided arguments");
+
+    assert_eq!(password.get(&args), Some(Ok("hunter2".to_string())));
+    assert_eq!(
+        password.source(&args),
+        &ValueSource::Env("PASSWORD_FILE".to_string())
+    );
+    assert_eq!(direct.get(&args), Some(Ok("direct".to_string())));
+    assert_eq!(disabled.get(&args), None);
+    assert_eq!(missing.get(&args), None);
+
+    let err = parser
+        .parse_provided(["test"], [("MISSING_FILE", "/nonexistent/secret")])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ArgParseError::Io("/nonexistent/secret".into(), std::io::ErrorKind::NotFound)
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn merge_policies() {
+    let mut parser = ArgumentReader::new();
+    let overridden = parser.add::<Vec<String>>(tag::long("over").env("OVER"));
+    let appended = parser.add::<Vec<String>>(
+        tag::long("append")
+            .env("APPEND")
+            .merge_policy(MergePolicy::Append),
+    );
+    let env_wins = parser.add::<u32>(
+        tag::long("port")
+            .env("PORT")
+            .merge_policy(MergePolicy::EnvWins),
+    );
+    let cli_only = parser.add::<u32>(
+        tag::long("other")
+            .env("OTHER")
+            .merge_policy(MergePolicy::EnvWins),
+    );
+
+    let env = [("OVER", "e1,e2"), ("APPEND", "e1,e2"), ("PORT", "80")];
+    let cli = [
+        "test", "--over", "c1", "--append", "c1", "--append", "c2", "--port", "8080", "--other",
+        "1",
+    ];
+
+    let args = parser
+        .parse_provided(cli, env)
+        .expect("failed to parse provided arguments");
+
+    assert_eq!(overridden.get(&args), Some(Ok(vec!["c1".to_string()])));
+    assert_eq!(
+        appended.get(&args),
+        Some(Ok(vec![
+            "e1".to_string(),
+            "e2".to_string(),
+            "c1".to_string(),
+            "c2".to_string(),
+        ]))
+    );
+    assert_eq!(env_wins.get(&args), Some(Ok(80)));
+    assert_eq!(
+        env_wins.source(&args),
+        &ValueSource::Env("PORT".to_string())
+    );
+    assert_eq!(cli_only.get(&args), Some(Ok(1)));
+    assert_eq!(args.remainder(), &["test"]);
+}
+
+#[test]
+fn reader_merge_policy() {
+    let mut parser = ArgumentReader::new();
+    let list = parser.add::<Vec<u32>>(tag::long("list").env("LIST"));
+    let single = parser.add::<u32>(tag::long("single").env("SINGLE"));
+    parser.merge_policy(MergePolicy::Append);
+
+    let args = parser
+        .parse_provided(
+            ["test", "--list", "3", "--single", "2"],
+            [("LIST", "1,2"), ("SINGLE", "1")],
+        )
+        .expect("failed to parse provided arguments");
+
+    assert_eq!(list.get(&args), Some(Ok(vec![1, 2, 3])));
+    assert_eq!(single.get(&args), Some(Ok(2)));
+}
+
+#[test]
+fn reusable_reader() {
+    let mut parser = ArgumentReader::new();
+    let name = parser.add::<String>(tag::long("name").env("NAME"));
+    let list = parser.add::<Vec<u32>>(tag::short('l'));
+
+    let first = parser
+        .parse_ref(
+            ["test", "--name", "a", "-l", "1", "-l", "2"],
+            [("NAME", "env")],
+        )
+        .expect("failed to parse first arguments");
+    let second = parser
+        .parse_ref(["test", "-l", "3"], None::<(&str, &str)>)
+        .expect("failed to parse second arguments");
+
+    assert_eq!(name.get(&first), Some(Ok("a".to_string())));
+    assert_eq!(list.get(&first), Some(Ok(vec![1, 2])));
+    assert_eq!(name.get(&second), None);
+    assert_eq!(list.get(&second), Some(Ok(vec![3])));
+    assert_eq!(second.remainder(), &["test"]);
+}
+
+#[test]
+fn parse_str() {
+    let mut parser = ArgumentReader::new();
+    let env = parser.add::<String>(tag::long("env"));
+    let force = parser.add::<bool>(tag::short('f'));
+    let tags = parser.add::<Vec<String>>(tag::short('t'));
+
+    let args = parser
+        .parse_str(r#"deploy --env 'prod east' -f -t "a \"b\"" #general"#)
+        .expect("failed to parse line");
+
+    assert_eq!(args.remainder(), &["deploy", "#general"]);
+    assert_eq!(env.get(&args), Some(Ok("prod east".to_string())));
+    assert_eq!(force.get(&args), Some(Ok(true)));
+    assert_eq!(tags.get(&args), Some(Ok(vec!["a \"b\"".to_string()])));
+
+    let err = parser.parse_str("deploy --env 'prod").unwrap_err();
+    assert_eq!(err, ArgParseError::UnterminatedQuote(None, 1, 14));
+    assert_eq!(err.to_string(), "Unterminated quote at line 1, column 14");
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::prelude::*;
+use crate::shell;
+
+/// Writes `contents` to a uniquely-named file in the temp directory.
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("sarge-{}-{name}", std::process::id()));
+    fs::write(&path, contents).expect("failed to write temp file");
+    path
+}
+
+#[test]
+fn shell_split() {
+    assert_eq!(
+        shell::split("a 'b c' \"d \\\"e\\\" \\n\" f\\ g # comment\nh#i", true),
+        Ok(vec![
+            "a".to_string(),
+            "b c".to_string(),
+            "d \"e\" \\n".to_string(),
+            "f g".to_string(),
+            "h#i".to_string(),
+        ])
+    );
+    assert_eq!(shell::split("  \n ", true), Ok(vec![]));
+    assert_eq!(shell::split("''", true), Ok(vec![String::new()]));
+
+    let err = shell::split("ok\n  'open", true).unwrap_err();
+    assert_eq!((err.line, err.column), (2, 3));
+}
+
+#[test]
+fn arg_files_expand() {
+    let inner = temp_file("inner.txt", "--count 3\n");
+    let outer = temp_file(
+        "outer.txt",
+        &format!("--name 'John Doe'\n# comment\n@{}\n", inner.display()),
+    );
+
+    let mut parser = ArgumentReader::new();
+    let name = parser.add::<String>(tag::long("name"));
+    let count = parser.add::<u32>(tag::long("count"));
+    parser.arg_files(true);
+
+    let outer_arg = format!("@{}", outer.display());
+    let args = parser
+        .clone()
+        .parse_cli(["test", outer_arg.as_str(), "rest", "@"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(name.get(&args), Some(Ok("John Doe".to_string())));
+    assert_eq!(count.get(&args), Some(Ok(3)));
+    assert_eq!(args.remainder(), &["test", "rest", "@"]);
+
+    // Disabled by default.
+    let mut plain = ArgumentReader::new();
+    let _name = plain.add::<String>(tag::long("name"));
+    let args = plain
+        .parse_cli(["test", outer_arg.as_str()])
+        .expect("failed to parse arguments");
+    assert_eq!(args.remainder(), &["test", outer_arg.as_str()]);
+
+    fs::remove_file(inner).unwrap();
+    fs::remove_file(outer).unwrap();
+}
+
+#[test]
+fn arg_file_errors() {
+    let mut parser = ArgumentReader::new();
+    parser.arg_files(true);
+
+    let recursive = std::env::temp_dir().join(format!("sarge-{}-self.txt", std::process::id()));
+    fs::write(&recursive, format!("@{}", recursive.display())).unwrap();
+    let arg = format!("@{}", recursive.display());
+    assert_eq!(
+        parser.clone().parse_cli([arg.as_str()]).unwrap_err(),
+        ArgParseError::ArgFileDepth(recursive.clone())
+    );
+    fs::remove_file(&recursive).unwrap();
+
+    let unterminated = temp_file("open.txt", "--a\n\"b");
+    let arg = format!("@{}", unterminated.display());
+    assert_eq!(
+        parser.clone().parse_cli([arg.as_str()]).unwrap_err(),
+        ArgParseError::UnterminatedQuote(Some(unterminated.clone()), 2, 1)
+    );
+    fs::remove_file(&unterminated).unwrap();
+
+    assert!(matches!(
+        parser.parse_cli(["@/nonexistent/sarge-args.txt"]),
+        Err(ArgParseError::Io(_, std::io::ErrorKind::NotFound))
+    ));
+}
@@ -0,0 +1,214 @@
+use crate::completions::{self, Completion};
+use crate::prelude::*;
+use crate::{Full, Shell};
+
+use std::ffi::OsString;
+
+fn tags() -> Vec<Full> {
+    vec![
+        tag::both('v', "verbose").doc("Print more output"),
+        tag::long("out-dir").doc("Where to put [generated] files: it's up to you"),
+        tag::short('I').doc("Add an include path"),
+        tag::env("ONLY_ENV"),
+    ]
+}
+
+fn render(shell: Shell) -> String {
+    let tags = tags();
+    let args: Vec<_> = tags
+        .iter()
+        .zip([(false, false), (true, false), (true, true), (true, false)])
+        .filter_map(|(tag, (takes_value, repeatable))| {
+            Completion::new(tag, takes_value, repeatable)
+        })
+        .collect();
+
+    completions::render(shell, "my-app", &args)
+}
+
+#[test]
+fn shell_from_str() {
+    assert_eq!("bash".parse(), Ok(Shell::Bash));
+    assert_eq!("ZSH".parse(), Ok(Shell::Zsh));
+    assert_eq!("fish".parse(), Ok(Shell::Fish));
+    assert!("cmd".parse::<Shell>().is_err());
+
+    let mut parser = ArgumentReader::new();
+    let shell = parser.add::<Shell>(tag::long("shell"));
+    let args = parser
+        .parse_provided(["--shell", "fish"], None::<(&str, &str)>)
+        .unwrap();
+    assert_eq!(shell.get(&args), Some(Ok(Shell::Fish)));
+}
+
+#[test]
+fn bash() {
+    assert_eq!(
+        render(Shell::Bash),
+        r#"_my_app() {
+    local cur prev
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    case "$prev" in
+        --out-dir|-I)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return 0
+            ;;
+    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "-v --verbose --out-dir -I" -- "$cur"))
+        return 0
+    fi
+
+    COMPREPLY=($(compgen -f -- "$cur"))
+}
+
+complete -F _my_app my-app
+"#
+    );
+}
+
+#[test]
+fn zsh() {
+    assert_eq!(
+        render(Shell::Zsh),
+        r"#compdef my-app
+
+_arguments -s \
+    '(-v --verbose)'{-v,--verbose}'[Print more output]' \
+    '--out-dir[Where to put \[generated\] files\: it'\''s up to you]:value:_files' \
+    '*-I[Add an include path]:value:_files' \
+    '*:file:_files'
+"
+    );
+}
+
+#[test]
+fn zsh_repeatable_both() {
+    let tag = tag::both('H', "header").doc("Header");
+    let args = [Completion::new(&tag, true, true).unwrap()];
+
+    // the `*` must be quoted, or zsh globs it away
+    assert!(completions::render(Shell::Zsh, "my-app", &args)
+        .contains("    '*'{-H,--header}'[Header]:value:_files' \\\n"));
+}
+
+#[test]
+fn fish() {
+    assert_eq!(
+        render(Shell::Fish),
+        r"complete -c my-app -s v -l verbose -d 'Print more output'
+complete -c my-app -l out-dir -r -d 'Where to put [generated] files: it\'s up to you'
+complete -c my-app -s I -r -d 'Add an include path'
+"
+    );
+}
+
+fn dynamic() -> ArgumentReader {
+    // completers can capture state, like a list loaded at startup
+    let clusters: Vec<String> = vec!["prod-eu".into(), "prod-us".into(), "staging".into()];
+
+    let mut parser = ArgumentReader::new();
+    parser.add::<String>(
+        tag::both('c', "cluster")
+            .doc("Cluster to deploy to")
+            .completer(move |_| clusters.clone()),
+    );
+    parser.add::<bool>(tag::long("dry-run").doc("Don't change anything"));
+    parser.add::<String>(tag::long("message"));
+    parser
+}
+
+#[test]
+fn dynamic_values() {
+    let parser = dynamic();
+
+    assert_eq!(
+        parser.complete(Shell::Bash, ["--cluster", "prod"]),
+        "prod-eu\nprod-us\n"
+    );
+    assert_eq!(
+        parser.complete(Shell::Fish, ["--dry-run", "-c", ""]),
+        "prod-eu\nprod-us\nstaging\n"
+    );
+    assert_eq!(
+        parser.complete(Shell::Zsh, ["--cluster=st"]),
+        "--cluster=staging\n"
+    );
+
+    // bash splits words on `=`, and only replaces the part after it
+    assert_eq!(
+        parser.complete(Shell::Bash, ["--cluster", "=", "st"]),
+        "staging\n"
+    );
+    assert_eq!(
+        parser.complete(Shell::Bash, ["--cluster", "="]),
+        "prod-eu\nprod-us\nstaging\n"
+    );
+
+    // no completer, or not a value
+    assert_eq!(parser.complete(Shell::Bash, ["--message", ""]), "");
+    assert_eq!(parser.complete(Shell::Bash, ["--dry-run", "pr"]), "");
+    assert_eq!(parser.complete(Shell::Bash, ["--", "-"]), "");
+}
+
+#[test]
+fn dynamic_flags() {
+    let parser = dynamic();
+
+    assert_eq!(
+        parser.complete(Shell::Bash, ["-"]),
+        "-c\n--cluster\n--dry-run\n--message\n"
+    );
+    assert_eq!(
+        parser.complete(Shell::Fish, ["--d"]),
+        "--dry-run\tDon't change anything\n"
+    );
+    assert_eq!(
+        parser.complete(Shell::Zsh, ["x", "--c"]),
+        "--cluster:Cluster to deploy to\n"
+    );
+}
+
+#[test]
+fn dynamic_register() {
+    assert_eq!(
+        completions::register(Shell::Bash, "my-app"),
+        r#"_my_app() {
+    local IFS=$'\n'
+    COMPREPLY=($(SARGE_COMPLETE=bash "${COMP_WORDS[0]}" "${COMP_WORDS[@]:1:COMP_CWORD}"))
+}
+
+complete -o default -F _my_app my-app
+"#
+    );
+    assert_eq!(
+        completions::register(Shell::Fish, "my-app"),
+        "complete -c my-app -a '(env SARGE_COMPLETE=fish my-app (commandline -opc)[2..-1] (commandline -ct | string collect -a))'\n"
+    );
+}
+
+#[test]
+fn dynamic_completion_is_opt_in() {
+    let request = || (Some(OsString::from("bash")), [OsString::from("--c")]);
+
+    let mut parser = dynamic();
+    let (shell, words) = request();
+    assert_eq!(parser.dynamic_complete(shell, words), None);
+
+    parser.dynamic_completion(true);
+    let (shell, words) = request();
+    assert_eq!(
+        parser.dynamic_complete(shell, words),
+        Some("--cluster\n".to_string())
+    );
+
+    // unset, or not a shell
+    assert_eq!(parser.dynamic_complete(None, [OsString::from("--c")]), None);
+    assert_eq!(
+        parser.dynamic_complete(Some("tcsh".into()), [OsString::from("--c")]),
+        None
+    );
+}
@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use crate::ValueSource;
+
+const CONFIG: &str = r#"
+# A comment
+name = "quoted \"name\""
+; another comment
+log_level = debug # trailing comment
+color = #fff
+verbose = true
+tags = [a, "b c", 'd,e', 'C:\dir']
+words = "f,g"
+
+[net]
+port = 8080
+"#;
+
+#[test]
+fn config_values() {
+    let mut parser = ArgumentReader::new();
+    let name = parser.add::<String>(tag::long("name"));
+    let level = parser.add::<String>(tag::long("log-level"));
+    let color = parser.add::<String>(tag::long("color"));
+    let verbose = parser.add::<bool>(tag::both('v', "verbose"));
+    let tags = parser.add::<Vec<String>>(tag::long("tags"));
+    let words = parser.add::<Vec<String>>(tag::long("words"));
+    let port = parser.add::<u16>(tag::long("net.port"));
+
+    parser.parse_config(CONFIG).expect("failed to parse config");
+    let args = parser
+        .parse_cli(["test"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(name.get(&args), Some(Ok("quoted \"name\"".to_string())));
+    assert_eq!(level.get(&args), Some(Ok("debug".to_string())));
+    assert_eq!(color.get(&args), Some(Ok("#fff".to_string())));
+    assert_eq!(verbose.get(&args), Some(Ok(true)));
+    assert_eq!(
+        tags.get(&args),
+        Some(Ok(vec![
+            "a".to_string(),
+            "b c".to_string(),
+            "d,e".to_string(),
+            "C:\\dir".to_string(),
+        ]))
+    );
+    // plain values are split like on the CLI
+    assert_eq!(
+        words.get(&args),
+        Some(Ok(vec!["f".to_string(), "g".to_string()]))
+    );
+    assert_eq!(port.get(&args), Some(Ok(8080)));
+    assert_eq!(port.source(&args), &ValueSource::Config(12));
+}
+
+#[test]
+fn config_precedence() {
+    let mut parser = ArgumentReader::new();
+    let a = parser.add::<u32>(tag::long("a").env("A"));
+    let b = parser.add::<u32>(tag::long("b").env("B"));
+    let c = parser.add::<u32>(tag::long("c").env("C"));
+
+    parser
+        .parse_config("a = 1\nb = 1\nc = 1")
+        .expect("failed to parse config");
+    let args = parser
+        .parse_provided(["test", "--c", "3"], [("B", "2"), ("C", "2")])
+        .expect("failed to parse arguments");
+
+    assert_eq!(a.get(&args), Some(Ok(1)));
+    assert_eq!(b.get(&args), Some(Ok(2)));
+    assert_eq!(c.get(&args), Some(Ok(3)));
+}
+
+#[test]
+fn config_errors() {
+    let mut parser = ArgumentReader::new();
+    let _single = parser.add::<u32>(tag::long("single"));
+
+    let err = |src: &str| parser.clone().parse_config(src).unwrap_err();
+
+    assert_eq!(
+        err("\nunknown = 1"),
+        ArgParseError::InvalidConfig(2, "unknown key `unknown`".to_string())
+    );
+    assert_eq!(
+        err("single = [1, 2]"),
+        ArgParseError::InvalidConfig(1, "`single` doesn't take a list".to_string())
+    );
+    assert_eq!(
+        err("# ok\n\nsingle"),
+        ArgParseError::InvalidConfig(3, "expected `key = value`".to_string())
+    );
+    assert_eq!(
+        err("single = \"open"),
+        ArgParseError::InvalidConfig(1, "unterminated string".to_string())
+    );
+    assert_eq!(
+        err("[section"),
+        ArgParseError::InvalidConfig(1, "unterminated section header".to_string())
+    );
+    assert_eq!(
+        err("single = \"1\" 2").to_string(),
+        "Invalid config on line 1: unexpected text after value: `2`"
+    );
+
+    assert!(matches!(
+        parser.parse_config_file("/nonexistent/sarge.conf"),
+        Err(ArgParseError::Io(_, std::io::ErrorKind::NotFound))
+    ));
+}
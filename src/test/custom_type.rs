@@ -13,6 +13,20 @@ impl ArgumentType for MyCustomType {
     }
 }
 
+/// Accepts non-UTF-8 values, but doesn't implement `from_os_value`.
+#[derive(Debug, PartialEq, Eq)]
+struct Lossy(String);
+
+impl ArgumentType for Lossy {
+    type Error = Infallible;
+
+    const ACCEPTS_NON_UTF8: bool = true;
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        Some(Ok(Self(val?.to_string())))
+    }
+}
+
 #[test]
 fn custom_type() {
     let mut parser = ArgumentReader::new();
@@ -37,3 +51,26 @@ fn custom_type() {
         ])))
     );
 }
+
+#[cfg(unix)]
+#[test]
+fn custom_type_non_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut parser = ArgumentReader::new();
+    let lossy = parser.add::<Lossy>(tag::long("lossy"));
+
+    let args = parser
+        .parse_provided_os(
+            [
+                OsStr::new("test"),
+                OsStr::new("--lossy"),
+                OsStr::from_bytes(b"a\xffb"),
+            ],
+            None::<(&OsStr, &OsStr)>,
+        )
+        .expect("failed to parse arguments");
+
+    assert_eq!(lossy.get(&args), Some(Ok(Lossy("a\u{fffd}b".to_string()))));
+}
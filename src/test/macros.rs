@@ -85,6 +85,29 @@ fn struct_attributes_are_applied() {
     assert!(rendered.contains("derived_flag"));
 }
 
+#[cfg(feature = "help")]
+#[test]
+fn unwrapped_fields_without_defaults_are_required() {
+    let required: Vec<_> = Args::schema()
+        .arguments
+        .into_iter()
+        .filter(|arg| arg.long.as_deref() != Some("help"))
+        .map(|arg| (arg.long.unwrap(), arg.required))
+        .collect();
+
+    assert_eq!(
+        required,
+        [
+            ("first-arg".to_string(), false),
+            ("second".to_string(), false),
+            ("third".to_string(), false),
+            ("fourth".to_string(), true),
+            ("fifth".to_string(), false),
+            ("sixth".to_string(), false),
+        ]
+    );
+}
+
 #[cfg(feature = "help")]
 #[test]
 fn doc_comments_are_used_for_help() {
@@ -93,6 +116,67 @@ fn doc_comments_are_used_for_help() {
     assert!(s.contains("DocComment test flag"));
 }
 
+#[cfg(feature = "help")]
+sarge! {
+    /// Builtin test args
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    #[sarge(
+        auto_help = true,
+        auto_version = true,
+        version = "0.1.0",
+        name = "builtin",
+        help_width = 80,
+    )]
+    BuiltinArgs,
+
+    /// Required, unless asking for help
+    name: String,
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn builtin_flags() {
+    match BuiltinArgs::parse_cli(["test", "-h"]) {
+        Err(ArgParseError::HelpRequested(help)) => {
+            assert_eq!(help, BuiltinArgs::help());
+            assert!(help.contains("Builtin test args"));
+            assert!(help.contains("Required, unless asking for help"));
+            assert!(help.contains("--help"));
+        }
+        other => panic!("expected help, got {other:?}"),
+    }
+
+    assert_eq!(
+        BuiltinArgs::parse_cli(["test", "--version"]).unwrap_err(),
+        ArgParseError::VersionRequested("builtin 0.1.0\n".into())
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_shows_values_and_defaults() {
+    let s = Args::help();
+    assert!(s.contains("--second <STRING>"));
+    assert!(s.contains("-t --third <N>"));
+    assert!(s.contains("-f --fourth <NUM>"));
+    assert!(s.contains("--fifth <N>"));
+    assert!(s.contains("[default: 1]"));
+    assert!(s.contains("[default: 0]"));
+    assert!(s.contains("--first-arg\n"));
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_shows_default_values_not_source() {
+    let s = DefaultArgs::help();
+    assert!(s.contains("[default: 127.0.0.1:9912]"));
+    assert!(s.contains("[default: 42]"));
+    assert!(s.contains("[default: true]"));
+    // no `Display`, so `Debug` is used
+    assert!(s.contains(r#"[default: ["{\"name\":\"hello\"}"]]"#));
+}
+
 #[cfg(feature = "help")]
 #[test]
 fn derived_args_docs_are_used_for_help() {
@@ -145,26 +229,38 @@ mod polluted_ok_import {
     }
 }
 
-sarge! {
-    #[derive(Debug, PartialEq, Eq)]
-    DefaultArgs,
+// `help_width` only exists on feature `help`, and pins the width so help
+// doesn't depend on `COLUMNS`.
+macro_rules! default_args {
+    ( $( $opt:tt )* ) => {
+        sarge! {
+            #[derive(Debug, PartialEq, Eq)]
+            #[sarge($( $opt )*)]
+            DefaultArgs,
 
-    // Default value (String).
-    socket_addr: String = "127.0.0.1:9912",
+            // Default value (String).
+            socket_addr: String = "127.0.0.1:9912",
 
-    // `#ok` default is a plain value; macro wraps it in `Some(...)`.
-    #ok 't' target_addr: String = "127.0.0.1:9911",
+            // `#ok` default is a plain value; macro wraps it in `Some(...)`.
+            #ok 't' target_addr: String = "127.0.0.1:9911",
 
-    // `#ok + default` applies only to missing values; parse failures become `None`.
-    #ok 'n' num: u32 = 42,
+            // `#ok + default` applies only to missing values; parse failures become `None`.
+            #ok 'n' num: u32 = 42,
 
-    // `#err` default is a plain value (not `Some(Ok(...))`).
-    #err 'h' help: bool = true,
+            // `#err` default is a plain value (not `Some(Ok(...))`).
+            #err 'h' help: bool = true,
 
-    // `Vec<String>` defaults can be specified without `.into()` per element.
-    #ok 'd' data: Vec<String> = vec![r#"{"name":"hello"}"#],
+            // `Vec<String>` defaults can be specified without `.into()` per element.
+            #ok 'd' data: Vec<String> = vec![r#"{"name":"hello"}"#],
+        }
+    };
 }
 
+#[cfg(feature = "help")]
+default_args!(help_width = 80);
+#[cfg(not(feature = "help"))]
+default_args!();
+
 #[cfg(feature = "macros")]
 sarge! {
     RepeatableVecArgs,
@@ -177,6 +273,27 @@ sarge! {
     #ok @HEADERS headers: Vec<String>,
 }
 
+sarge! {
+    #[derive(Debug)]
+    OptionArgs,
+    name: Option<String>,
+}
+
+#[test]
+fn option_without_wrapper() {
+    let (args, _) = OptionArgs::parse_cli(["x"]).unwrap();
+    assert_eq!(args.name, None);
+
+    let (args, _) = OptionArgs::parse_cli(["x", "--name", "a"]).unwrap();
+    assert_eq!(args.name.as_deref(), Some("a"));
+
+    // a trailing flag is an error, not a panic
+    assert_eq!(
+        OptionArgs::parse_cli(["x", "--name"]).unwrap_err(),
+        ArgParseError::MissingValue("name".into())
+    );
+}
+
 // Test matrix: wrapper type (none/#ok/#err) × default (none/some) × input
 // (missing/parse ok/parse err).
 sarge! {
@@ -410,3 +527,50 @@ fn plain_default_parse_success_overrides_default() {
 fn plain_default_parse_failure_panics() {
     let _ = PlainDefaultArgs::parse_cli(["bin", "--num", "bad"]);
 }
+
+#[cfg(unix)]
+#[test]
+fn parse_provided_os_keeps_raw_remainder() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let bad = OsStr::from_bytes(b"\xffraw");
+    let (args, remainder) =
+        PlainDefaultArgs::parse_provided_os([OsStr::new("bin"), bad], None::<(&str, &str)>)
+            .expect("failed to parse non-UTF-8 remainder");
+
+    assert_eq!(args.num, 7);
+    assert_eq!(remainder, vec![OsStr::new("bin"), bad]);
+}
+
+sarge! {
+    #[sarge(env_prefix = "MYAPP_")]
+    #[derive(Debug, PartialEq, Eq)]
+    PrefixedArgs,
+
+    #ok log_level: String,
+    #ok @EXPLICIT_PORT port: u16,
+}
+
+#[test]
+fn env_prefix_option() {
+    let env = [
+        ("MYAPP_LOG_LEVEL", "debug"),
+        ("MYAPP_PORT", "1"),
+        ("EXPLICIT_PORT", "2"),
+    ];
+
+    let (args, _) =
+        PrefixedArgs::parse_provided(["bin"], env).expect("failed to parse prefixed args");
+
+    assert_eq!(args.log_level.as_deref(), Some("debug"));
+    assert_eq!(args.port, Some(2));
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn env_prefix_option_help() {
+    let s = PrefixedArgs::help();
+    assert!(s.contains("$MYAPP_LOG_LEVEL"));
+    assert!(s.contains("$EXPLICIT_PORT"));
+}
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+fn reader() -> ArgumentReader {
+    let mut parser = ArgumentReader::new();
+    parser.doc = Some("Deploys \"things\".".into());
+    parser.add::<u32>(
+        tag::both('p', "page")
+            .env("PAGE")
+            .default_value(1)
+            .doc("Page to show"),
+    );
+    parser.add::<bool>(tag::long("dry-run"));
+    parser.add::<Vec<Option<String>>>(
+        tag::env("TOKENS")
+            .env_file(true)
+            .required(true)
+            .doc("Tokens | keys\nsecond line"),
+    );
+    parser
+}
+
+#[test]
+fn required_is_descriptive() {
+    let parser = reader();
+    assert!(parser.schema().arguments[2].required);
+    assert!(parser.parse_ref(["x"], None::<(&str, &str)>).is_ok());
+}
+
+#[test]
+fn markdown() {
+    let mut schema = reader().schema();
+    schema.name = "app".into();
+
+    assert_eq!(
+        schema.to_markdown(),
+        r#"# app
+
+Deploys "things".
+
+## Arguments
+
+| Flags | Environment | Value | Default | Description |
+| --- | --- | --- | --- | --- |
+| `-p`, `--page` | `PAGE` | `N` | `1` | Page to show |
+| `--dry-run` |  |  |  |  |
+|  | `TOKENS` | `STRING` |  | Tokens \| keys<br>second line **(required)** |
+"#
+    );
+}
+
+#[test]
+fn json() {
+    let mut schema = reader().schema();
+    schema.name = "app".into();
+    schema.arguments.truncate(2);
+    schema.arguments[1].doc = Some("Tab\there".into());
+
+    assert_eq!(
+        schema.to_json(),
+        r#"{
+  "name": "app",
+  "doc": "Deploys \"things\".",
+  "arguments": [
+    {
+      "short": "p",
+      "long": "page",
+      "env": "PAGE",
+      "env_file": false,
+      "value_name": "N",
+      "takes_value": true,
+      "repeatable": false,
+      "default": "1",
+      "required": false,
+      "doc": "Page to show"
+    },
+    {
+      "short": null,
+      "long": "dry-run",
+      "env": null,
+      "env_file": false,
+      "value_name": null,
+      "takes_value": false,
+      "repeatable": false,
+      "default": null,
+      "required": false,
+      "doc": "Tab\there"
+    }
+  ]
+}
+"#
+    );
+}
@@ -0,0 +1,411 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::prelude::*;
+use crate::types::{
+    BoolError, ByteSize, ByteSizeError, DurationError, HumanDuration, KeyValueError, LenientBool,
+    LiteralInt, RangeError,
+};
+use crate::ArgumentType;
+use crate::Delimiter;
+
+#[test]
+fn std_types() {
+    let mut parser = ArgumentReader::new();
+    let path = parser.add::<PathBuf>(tag::long("path"));
+    let os = parser.add::<OsString>(tag::long("os"));
+    let ch = parser.add::<char>(tag::long("char"));
+    let nonzero = parser.add::<NonZeroU32>(tag::long("nonzero"));
+
+    let args = [
+        "test",
+        "--path",
+        "/tmp/x",
+        "--os",
+        "hello",
+        "--char",
+        "c",
+        "--nonzero",
+        "0",
+    ];
+    let args = parser.parse_cli(args).expect("failed to parse arguments");
+
+    assert_eq!(path.get(&args), Some(Ok(PathBuf::from("/tmp/x"))));
+    assert_eq!(os.get(&args), Some(Ok(OsString::from("hello"))));
+    assert_eq!(ch.get(&args), Some(Ok('c')));
+    assert!(matches!(nonzero.get(&args), Some(Err(_))));
+}
+
+#[test]
+fn net_types() {
+    let mut parser = ArgumentReader::new();
+    let ip = parser.add::<IpAddr>(tag::long("ip"));
+    let v4 = parser.add::<Ipv4Addr>(tag::long("v4"));
+    let v6 = parser.add::<Ipv6Addr>(tag::long("v6"));
+    let sock = parser.add::<SocketAddr>(tag::long("sock"));
+
+    let args = [
+        "test",
+        "--ip",
+        "::1",
+        "--v4",
+        "10.0.0.1",
+        "--v6",
+        "not-an-ip",
+        "--sock",
+        "127.0.0.1:8080",
+    ];
+    let args = parser.parse_cli(args).expect("failed to parse arguments");
+
+    assert_eq!(ip.get(&args), Some(Ok(IpAddr::V6(Ipv6Addr::LOCALHOST))));
+    assert_eq!(v4.get(&args), Some(Ok(Ipv4Addr::new(10, 0, 0, 1))));
+    assert!(matches!(v6.get(&args), Some(Err(_))));
+    assert_eq!(
+        sock.get(&args),
+        Some(Ok(SocketAddr::from(([127, 0, 0, 1], 8080))))
+    );
+}
+
+#[test]
+fn option_type() {
+    let mut parser = ArgumentReader::new();
+    let given = parser.add::<Option<u32>>(tag::long("given"));
+    let missing = parser.add::<Option<u32>>(tag::long("missing"));
+
+    let args = parser
+        .parse_cli(["test", "--given", "5"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(given.get(&args), Some(Ok(Some(5))));
+    assert_eq!(missing.get(&args), Some(Ok(None)));
+
+    let mut parser = ArgumentReader::new();
+    let color = parser.add::<Option<bool>>(tag::long("color"));
+
+    let args = parser.parse_str("").unwrap();
+    assert_eq!(color.get(&args), Some(Ok(None)));
+    let args = parser.parse_str("--color").unwrap();
+    assert_eq!(color.get(&args), Some(Ok(Some(true))));
+    let args = parser.parse_str("--color=off").unwrap();
+    assert_eq!(color.get(&args), Some(Ok(Some(false))));
+}
+
+#[test]
+fn human_duration() {
+    let parse = |s: &str| s.parse::<HumanDuration>().map(Duration::from);
+
+    assert_eq!(parse("90"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse("1m30s"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse("1h 2m"), Ok(Duration::from_mins(62)));
+    assert_eq!(parse("1.5h"), Ok(Duration::from_mins(90)));
+    assert_eq!(parse("250ms"), Ok(Duration::from_millis(250)));
+    assert_eq!(parse("3y"), Err(DurationError::UnknownUnit("y".into())));
+    assert_eq!(parse("1m30"), Err(DurationError::MissingUnit("30".into())));
+    assert_eq!(
+        parse("1.2.3s"),
+        Err(DurationError::InvalidNumber("1.2.3".into()))
+    );
+    assert_eq!(parse(""), Err(DurationError::Empty));
+
+    assert_eq!(HumanDuration(Duration::from_secs(90)).to_string(), "1m30s");
+    assert_eq!(
+        HumanDuration(Duration::from_millis(1500)).to_string(),
+        "1s500ms"
+    );
+    assert_eq!(HumanDuration(Duration::ZERO).to_string(), "0s");
+}
+
+#[test]
+fn byte_size() {
+    let parse = |s: &str| s.parse::<ByteSize>().map(u64::from);
+
+    assert_eq!(parse("1024"), Ok(1024));
+    assert_eq!(parse("512MiB"), Ok(512 * 1024 * 1024));
+    assert_eq!(parse("64k"), Ok(64_000));
+    assert_eq!(parse("2gi"), Ok(2 * 1024 * 1024 * 1024));
+    assert_eq!(parse("1.5GB"), Ok(1_500_000_000));
+    assert_eq!(parse("3XB"), Err(ByteSizeError::UnknownUnit("XB".into())));
+    assert_eq!(parse("99EiB"), Err(ByteSizeError::Overflow));
+
+    assert_eq!(ByteSize(512 * 1024 * 1024).to_string(), "512MiB");
+    assert_eq!(ByteSize(64_000).to_string(), "64kB");
+    assert_eq!(ByteSize(1023).to_string(), "1023B");
+}
+
+#[test]
+fn duration_argument() {
+    let mut parser = ArgumentReader::new();
+    let timeout = parser.add::<Duration>(tag::long("timeout"));
+    let cache = parser.add::<ByteSize>(tag::long("cache"));
+
+    let args = parser
+        .parse_cli(["test", "--timeout", "1m30s", "--cache", "oops"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(timeout.get(&args), Some(Ok(Duration::from_secs(90))));
+    assert_eq!(
+        cache.get(&args),
+        Some(Err(ByteSizeError::InvalidNumber("oops".into())))
+    );
+}
+
+#[test]
+fn repeated_values_are_kept_separate() {
+    let mut parser = ArgumentReader::new();
+    let headers = parser.add::<Vec<String>>(tag::short('H').delimiter(Delimiter::None));
+
+    let args = parser
+        .parse_cli(["test", "-H", "Accept: a,b", "-H", "X-Foo: c"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(
+        headers.get(&args),
+        Some(Ok(vec!["Accept: a,b".to_string(), "X-Foo: c".to_string()]))
+    );
+}
+
+#[test]
+fn custom_delimiters() {
+    let mut parser = ArgumentReader::new();
+    let path = parser.add::<Vec<PathBuf>>(tag::env("SEARCH_PATH").delimiter(Delimiter::Char(':')));
+    let words = parser.add::<Vec<String>>(tag::long("words").delimiter(Delimiter::Whitespace));
+
+    let args = parser
+        .parse_provided(
+            ["test", "--words", "  one two\\ three\tfour "],
+            [("SEARCH_PATH", "/usr/bin:/odd\\:dir:/bin")],
+        )
+        .expect("failed to parse arguments");
+
+    assert_eq!(
+        path.get(&args),
+        Some(Ok(vec![
+            PathBuf::from("/usr/bin"),
+            PathBuf::from("/odd:dir"),
+            PathBuf::from("/bin"),
+        ]))
+    );
+    assert_eq!(
+        words.get(&args),
+        Some(Ok(vec![
+            "one".to_string(),
+            "two three".to_string(),
+            "four".to_string(),
+        ]))
+    );
+}
+
+#[test]
+fn escaped_delimiters() {
+    let split = |s: &str| {
+        Delimiter::default()
+            .split(OsStr::new(s))
+            .into_iter()
+            .map(|s| s.into_string().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(split("a\\,b,c"), ["a,b", "c"]);
+    assert_eq!(split("a\\\\,b"), ["a\\", "b"]);
+    assert_eq!(split("C:\\dir,x"), ["C:\\dir", "x"]);
+    assert_eq!(split("a,,b"), ["a", "", "b"]);
+    assert_eq!(split("é,ü"), ["é", "ü"]);
+
+    // backslashes not before a delimiter are left alone
+    assert_eq!(
+        split(r"\\server\share,C:\dir"),
+        [r"\\server\share", r"C:\dir"]
+    );
+    assert_eq!(split(r"a\\\,b"), [r"a\,b"]);
+
+    for val in [r"\\server\share", r"a\,b", r"a\\", "x,y", r"\d+\\"] {
+        let escaped = Delimiter::default().escape(val);
+        assert_eq!(split(&escaped), [val], "{escaped}");
+    }
+}
+
+#[test]
+fn unc_paths() {
+    let mut parser = ArgumentReader::new();
+    let paths = parser.add::<Vec<PathBuf>>(tag::long("path"));
+
+    let args = parser
+        .parse_cli(["test", "--path", r"\\server\share,C:\dir"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(
+        paths.get(&args),
+        Some(Ok(vec![
+            PathBuf::from(r"\\server\share"),
+            PathBuf::from(r"C:\dir"),
+        ]))
+    );
+}
+
+#[test]
+fn key_value_types() {
+    let mut parser = ArgumentReader::new();
+    let defines = parser.add::<Vec<(String, String)>>(tag::short('D'));
+    let sets =
+        parser.add::<BTreeMap<String, u32>>(tag::long("set").delimiter(Delimiter::Char(',')));
+    let hashed = parser.add::<HashMap<String, String>>(tag::long("env"));
+
+    let args = parser
+        .parse_cli([
+            "test",
+            "-D",
+            "b=2",
+            "-D",
+            "a=x=y",
+            "-D",
+            "CFLAGS=-O2,-g",
+            "--set",
+            "a.b=1,c=2",
+            "--set",
+            "a.b=3",
+            "--env",
+            "LIST=a,b",
+        ])
+        .expect("failed to parse arguments");
+
+    assert_eq!(
+        defines.get(&args),
+        Some(Ok(vec![
+            ("b".to_string(), "2".to_string()),
+            ("a".to_string(), "x=y".to_string()),
+            ("CFLAGS".to_string(), "-O2,-g".to_string()),
+        ]))
+    );
+    assert_eq!(
+        sets.get(&args),
+        Some(Ok(BTreeMap::from([
+            ("a.b".to_string(), 3),
+            ("c".to_string(), 2),
+        ])))
+    );
+    assert_eq!(
+        hashed.get(&args),
+        Some(Ok(HashMap::from([("LIST".to_string(), "a,b".to_string())])))
+    );
+}
+
+#[test]
+fn key_value_errors() {
+    let mut parser = ArgumentReader::new();
+    let missing = parser.add::<Vec<(String, u32)>>(tag::long("missing"));
+    let bad = parser.add::<HashMap<String, u32>>(tag::long("bad"));
+
+    let args = parser
+        .parse_cli(["test", "--missing", "novalue", "--bad", "n=x"])
+        .expect("failed to parse arguments");
+
+    let err = missing.get(&args).unwrap().unwrap_err();
+    assert_eq!(err, KeyValueError::MissingSeparator("novalue".to_string()));
+    assert_eq!(err.to_string(), "Expected `key=value`, found `novalue`");
+    assert!(matches!(bad.get(&args), Some(Err(KeyValueError::Value(_)))));
+}
+
+#[test]
+fn bool_values() {
+    let parse = |s: &str| bool::from_value(Some(s)).unwrap();
+
+    for t in ["true", "TRUE", "t", "yes", "Y", "on", "1"] {
+        assert_eq!(parse(t), Ok(true), "{t}");
+    }
+
+    for f in ["false", "F", "no", "n", "OFF", "0", ""] {
+        assert_eq!(parse(f), Ok(false), "{f}");
+    }
+
+    assert_eq!(parse("ture"), Err(BoolError("ture".to_string())));
+    assert_eq!(bool::from_value(None), Some(Ok(true)));
+}
+
+#[test]
+fn bool_cli_values() {
+    let mut parser = ArgumentReader::new();
+    let flag = parser.add::<bool>(tag::both('f', "flag"));
+
+    let args = parser.parse_str("--flag=no").unwrap();
+    assert_eq!(flag.get(&args), Some(Ok(false)));
+
+    let args = parser.parse_str("--flag=yes").unwrap();
+    assert_eq!(flag.get(&args), Some(Ok(true)));
+
+    let args = parser.parse_str("--flag=maybe").unwrap();
+    assert_eq!(flag.get(&args), Some(Err(BoolError("maybe".to_string()))));
+
+    // a bare flag doesn't consume the next argument
+    let args = parser.parse_str("-f no").unwrap();
+    assert_eq!(flag.get(&args), Some(Ok(true)));
+    assert_eq!(args.remainder(), ["no"]);
+}
+
+#[test]
+fn lenient_bool() {
+    let mut parser = ArgumentReader::new();
+    let strict = parser.add::<bool>(tag::env("STRICT"));
+    let lenient = parser.add::<LenientBool>(tag::env("LENIENT"));
+    let flag = parser.add::<LenientBool>(tag::long("flag"));
+
+    let args = parser
+        .parse_provided(["test", "--flag"], [("STRICT", "yes"), ("LENIENT", "yes")])
+        .expect("failed to parse arguments");
+
+    assert_eq!(strict.get(&args), Some(Ok(true)));
+    assert_eq!(lenient.get(&args), Some(Ok(LenientBool(false))));
+    assert_eq!(flag.get(&args), Some(Ok(LenientBool(true))));
+}
+
+#[test]
+fn literal_ints() {
+    let parse = |s: &str| s.parse::<LiteralInt<i64>>().map(|i| i.0);
+
+    assert_eq!(parse("0x7fff0000"), Ok(0x7fff_0000));
+    assert_eq!(parse("0B1010"), Ok(0b1010));
+    assert_eq!(parse("0o17"), Ok(0o17));
+    assert_eq!(parse("-0x10"), Ok(-16));
+    assert_eq!(parse("+1_000_000"), Ok(1_000_000));
+    assert!(parse("0x-5").is_err());
+    assert!(parse("0xg").is_err());
+    assert!("-1".parse::<LiteralInt<u8>>().is_err());
+
+    let mut parser = ArgumentReader::new();
+    let mask = parser.add::<LiteralInt<u32>>(tag::long("mask"));
+    let plain = parser.add::<u32>(tag::long("plain"));
+
+    let args = parser
+        .parse_cli(["test", "--mask", "0xff", "--plain", "0xff"])
+        .expect("failed to parse arguments");
+
+    assert_eq!(mask.get(&args), Some(Ok(LiteralInt(255))));
+    assert!(matches!(plain.get(&args), Some(Err(_))));
+}
+
+#[test]
+fn range_inclusive() {
+    let parse = |s: &str| RangeInclusive::<i32>::from_value(Some(s)).unwrap();
+
+    assert_eq!(parse("10..20"), Ok(10..=20));
+    assert_eq!(parse("10..=20"), Ok(10..=20));
+    assert_eq!(parse("5-8"), Ok(5..=8));
+    assert_eq!(parse("-5--1"), Ok(-5..=-1));
+    assert_eq!(parse("3..3"), Ok(3..=3));
+    assert_eq!(parse("8-5"), Err(RangeError::Reversed("8-5".to_string())));
+    assert_eq!(
+        parse("5"),
+        Err(RangeError::MissingSeparator("5".to_string()))
+    );
+    assert!(matches!(parse("x..5"), Err(RangeError::Start(_))));
+    assert!(matches!(parse("1..y"), Err(RangeError::End(_))));
+
+    assert_eq!(
+        RangeInclusive::<LiteralInt<u16>>::from_value(Some("0x10..0x1_f")),
+        Some(Ok(LiteralInt(16)..=LiteralInt(31)))
+    );
+}
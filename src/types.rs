@@ -1,7 +1,174 @@
 //! All interfaces for handling argument types.
 
+use std::char::ParseCharError;
 use std::convert::Infallible;
-use std::num::{ParseFloatError, ParseIntError};
+use std::error::Error;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{self, Display};
+use std::net::{
+    AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
+};
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, ParseFloatError, ParseIntError,
+};
+use std::path::PathBuf;
+
+mod human;
+pub use human::{ByteSize, ByteSizeError, DurationError, HumanDuration};
+
+mod map;
+pub use map::KeyValueError;
+
+mod number;
+pub use number::{LiteralInt, RangeError};
+
+/// How a single value of a list argument (such as `Vec<T>`) is split into
+/// elements. Set per-argument via [`Full::delimiter`](crate::tag::Full::delimiter).
+///
+/// A delimiter can be included literally by escaping it with a backslash
+/// (`a\,b`), and a literal backslash before a delimiter with another
+/// (`a\\,b` is `a\` and `b`). Only backslashes directly before a delimiter
+/// are unescaped; any other backslash is left alone, so `C:\dir` and
+/// `\\server\share` pass through unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Delimiter {
+    /// Split on the given character, e.g. `,` (the default) or `:` for
+    /// `PATH`-style values.
+    Char(char),
+    /// Split on runs of whitespace, ignoring leading and trailing whitespace.
+    Whitespace,
+    /// Never split; each occurrence of the argument is exactly one element.
+    None,
+}
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Self::Char(',')
+    }
+}
+
+impl Delimiter {
+    fn matches(self, ch: char) -> bool {
+        match self {
+            Self::Char(d) => ch == d,
+            Self::Whitespace => ch.is_whitespace(),
+            Self::None => false,
+        }
+    }
+
+    /// Splits `val` into its elements, unescaping any escaped delimiters.
+    pub fn split(self, val: &OsStr) -> Vec<OsString> {
+        if self == Self::None {
+            return vec![val.to_os_string()];
+        }
+
+        let bytes = val.as_encoded_bytes();
+        // SAFETY: every slice is taken from the encoded bytes of an `OsStr`,
+        // and only ever split immediately before or after an ASCII backslash
+        // or a delimiter character (itself valid UTF-8).
+        let slice = |from: usize, to: usize| unsafe {
+            OsStr::from_encoded_bytes_unchecked(&bytes[from..to])
+        };
+
+        let mut out = Vec::new();
+        let mut current = OsString::new();
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let ch = next_char(&bytes[i..]);
+
+            if ch == Some('\\') {
+                // a run of backslashes is only unescaped before a delimiter:
+                // each pair is one backslash, and an odd one out escapes it
+                let run = bytes[i..].iter().take_while(|&&b| b == b'\\').count();
+                let end = i + run;
+                match next_char(&bytes[end..]).filter(|&next| self.matches(next)) {
+                    Some(next) => {
+                        current.push(slice(start, i));
+                        current.push("\\".repeat(run / 2));
+                        if run % 2 == 1 {
+                            current.push(slice(end, end + next.len_utf8()));
+                            i = end + next.len_utf8();
+                        } else {
+                            i = end;
+                        }
+                        start = i;
+                    }
+                    None => i = end,
+                }
+                continue;
+            } else if let Some(ch) = ch.filter(|&ch| self.matches(ch)) {
+                current.push(slice(start, i));
+                out.push(std::mem::take(&mut current));
+                i += ch.len_utf8();
+                start = i;
+                continue;
+            }
+
+            i += ch.map_or(1, char::len_utf8);
+        }
+
+        current.push(slice(start, bytes.len()));
+        out.push(current);
+
+        if self == Self::Whitespace {
+            out.retain(|s| !s.is_empty());
+        }
+
+        out
+    }
+
+    /// Escapes every delimiter in `val` (and any backslashes directly before
+    /// one), so that [`split`](Delimiter::split) returns it as a single
+    /// element.
+    pub(crate) fn escape(self, val: &str) -> String {
+        if self == Self::None {
+            return val.to_string();
+        }
+
+        let mut out = String::with_capacity(val.len());
+        let mut run = 0;
+        for ch in val.chars() {
+            if ch == '\\' {
+                run += 1;
+            } else {
+                if self.matches(ch) {
+                    // double the backslashes before it, then escape it
+                    out.push_str(&"\\".repeat(run + 1));
+                }
+                run = 0;
+            }
+            out.push(ch);
+        }
+
+        out
+    }
+}
+
+/// Splits `val` on the first occurrence of the ASCII character `delimiter`.
+pub(crate) fn split_once_os(val: &OsStr, delimiter: u8) -> Option<(&OsStr, &OsStr)> {
+    debug_assert!(delimiter.is_ascii());
+
+    let bytes = val.as_encoded_bytes();
+    let i = bytes.iter().position(|&b| b == delimiter)?;
+
+    // SAFETY: both halves are taken from the encoded bytes of an `OsStr`,
+    // split immediately before and after an ASCII character.
+    unsafe {
+        Some((
+            OsStr::from_encoded_bytes_unchecked(&bytes[..i]),
+            OsStr::from_encoded_bytes_unchecked(&bytes[i + 1..]),
+        ))
+    }
+}
+
+/// Decodes the UTF-8 character at the start of `bytes`, if any.
+fn next_char(bytes: &[u8]) -> Option<char> {
+    let len = bytes.len().min(4);
+    (1..=len).find_map(|n| std::str::from_utf8(&bytes[..n]).ok()?.chars().next())
+}
 
 /// The type returned when retrieving an argument.
 pub type ArgResult<T> = Option<Result<T, <T as ArgumentType>::Error>>;
@@ -10,8 +177,12 @@ pub type ArgResult<T> = Option<Result<T, <T as ArgumentType>::Error>>;
 pub type DefaultedArgResult<T> = Result<T, <T as ArgumentType>::Error>;
 
 /// A type that can be used as an argument.
-/// Implemented for `bool`, `i64`, `u64`, `f64`,
-/// `String`, and `Vec<T: ArgumentType>`.
+/// Implemented for `bool`, all the integer and float primitives (and their
+/// `NonZero*` counterparts), `char`, `String`, `OsString`, `PathBuf`, the
+/// `std::net` address types, `Duration`, `Option<T: ArgumentType>`,
+/// `Vec<T: ArgumentType>`, `key=value` pairs as `(K, V)`, `HashMap<K, V>`,
+/// or `BTreeMap<K, V>`, and `RangeInclusive<T>`. See also [`LiteralInt`],
+/// [`HumanDuration`], and [`ByteSize`].
 ///
 /// You can implement this for your own types! It's essentially the same as
 /// `FromStr`, with one crucial difference: you can specify a default value
@@ -38,14 +209,59 @@ pub trait ArgumentType: Sized {
     /// accumulate values instead of overwriting.
     ///
     /// This is primarily used for `Vec<T>`, so `-H a -H b` becomes `["a", "b"]`.
+    /// Each occurrence is kept separately and passed to
+    /// [`from_os_values`](ArgumentType::from_os_values).
     const REPEATABLE: bool = false;
 
+    /// Whether this type accepts values that aren't valid UTF-8, such as
+    /// arbitrary filenames. If so, implement
+    /// [`from_os_value`](ArgumentType::from_os_value) as well, or values
+    /// will be converted lossily.
+    ///
+    /// Otherwise, non-UTF-8 values are rejected while parsing with
+    /// [`ArgParseError::InvalidUnicode`](crate::ArgParseError::InvalidUnicode).
+    const ACCEPTS_NON_UTF8: bool = false;
+
+    /// The name of the value in help messages, e.g. `N` in `--foo <N>`.
+    /// Arguments can override this with
+    /// [`Full::value_name`](crate::tag::Full::value_name).
+    const VALUE_NAME: &'static str = "VALUE";
+
+    /// How values are split into elements when the argument doesn't set a
+    /// [`Full::delimiter`](crate::tag::Full::delimiter). Only list types
+    /// like `Vec<T>` split values, using their element's delimiter; `key=value`
+    /// pairs use [`Delimiter::None`], so values may contain commas.
+    const DELIMITER: Delimiter = Delimiter::Char(',');
+
     /// Perform parsing on the value.
     ///
     /// If the argument doesn't take any input, `val` is None.
     #[allow(clippy::missing_errors_doc)]
     fn from_value(val: Option<&str>) -> ArgResult<Self>;
 
+    /// Perform parsing on a raw OS string value.
+    ///
+    /// Unless [`ACCEPTS_NON_UTF8`](ArgumentType::ACCEPTS_NON_UTF8) is set, the
+    /// value is guaranteed to be valid UTF-8. This defaults to forwarding to
+    /// [`from_value`](ArgumentType::from_value), converting any invalid UTF-8
+    /// lossily.
+    #[allow(clippy::missing_errors_doc)]
+    fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+        Self::from_value(val.map(OsStr::to_string_lossy).as_deref())
+    }
+
+    /// Perform parsing on every value given to the argument, in order, along
+    /// with the argument's [`Delimiter`].
+    ///
+    /// Unless [`REPEATABLE`](ArgumentType::REPEATABLE) is set, there's at
+    /// most one value. This defaults to parsing only the last value with
+    /// [`from_os_value`](ArgumentType::from_os_value).
+    #[allow(clippy::missing_errors_doc)]
+    fn from_os_values(vals: &[&OsStr], delimiter: Delimiter) -> ArgResult<Self> {
+        let _ = delimiter;
+        Self::from_os_value(vals.last().copied())
+    }
+
     /// If no value was given, what the default should be, if any.
     /// This defaults to `None`.
     fn default_value() -> Option<Self> {
@@ -54,11 +270,13 @@ pub trait ArgumentType: Sized {
 }
 
 macro_rules! impl_intrinsics {
-    ( $( $typ:ty, $err:ty $( => $default:block )? );+ $(;)? ) => {
+    ( $( $typ:ty, $err:ty, $name:literal $( => $default:block )? );+ $(;)? ) => {
         $(
         impl ArgumentType for $typ {
             type Error = $err;
 
+            const VALUE_NAME: &'static str = $name;
+
             fn from_value(val: Option<&str>) -> ArgResult<Self> {
                 val.map(|val| val.parse())
             }
@@ -74,38 +292,170 @@ macro_rules! impl_intrinsics {
 }
 
 impl_intrinsics! {
-    i8, ParseIntError;
-    i16, ParseIntError;
-    i32, ParseIntError;
-    i64, ParseIntError;
-    i128, ParseIntError;
-    isize, ParseIntError;
-    u8, ParseIntError;
-    u16, ParseIntError;
-    u32, ParseIntError;
-    u64, ParseIntError;
-    u128, ParseIntError;
-    usize, ParseIntError;
-    f32, ParseFloatError;
-    f64, ParseFloatError;
-    String, Infallible;
+    i8, ParseIntError, "N";
+    i16, ParseIntError, "N";
+    i32, ParseIntError, "N";
+    i64, ParseIntError, "N";
+    i128, ParseIntError, "N";
+    isize, ParseIntError, "N";
+    u8, ParseIntError, "N";
+    u16, ParseIntError, "N";
+    u32, ParseIntError, "N";
+    u64, ParseIntError, "N";
+    u128, ParseIntError, "N";
+    usize, ParseIntError, "N";
+    f32, ParseFloatError, "NUM";
+    f64, ParseFloatError, "NUM";
+    NonZeroI8, ParseIntError, "N";
+    NonZeroI16, ParseIntError, "N";
+    NonZeroI32, ParseIntError, "N";
+    NonZeroI64, ParseIntError, "N";
+    NonZeroI128, ParseIntError, "N";
+    NonZeroIsize, ParseIntError, "N";
+    NonZeroU8, ParseIntError, "N";
+    NonZeroU16, ParseIntError, "N";
+    NonZeroU32, ParseIntError, "N";
+    NonZeroU64, ParseIntError, "N";
+    NonZeroU128, ParseIntError, "N";
+    NonZeroUsize, ParseIntError, "N";
+    char, ParseCharError, "CHAR";
+    String, Infallible, "STRING";
+    IpAddr, AddrParseError, "ADDR";
+    Ipv4Addr, AddrParseError, "ADDR";
+    Ipv6Addr, AddrParseError, "ADDR";
+    SocketAddr, AddrParseError, "ADDR";
+    SocketAddrV4, AddrParseError, "ADDR";
+    SocketAddrV6, AddrParseError, "ADDR";
 }
 
+/// An error from parsing a `bool`. Holds the unrecognized value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoolError(pub String);
+
+impl Display for BoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expected a boolean (true/false, yes/no, on/off, 1/0), found `{}`",
+            self.0
+        )
+    }
+}
+
+impl Error for BoolError {}
+
+/// Flags are `true` when passed without a value. Given a value (e.g. from an
+/// environment variable), `true`/`t`/`yes`/`y`/`on`/`1` are true and
+/// `false`/`f`/`no`/`n`/`off`/`0`/the empty string are false, ignoring case.
+/// Anything else is an error; see [`LenientBool`] for the old behavior.
 impl ArgumentType for bool {
+    type Error = BoolError;
+
+    const CONSUMES: bool = false;
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        let Some(val) = val else {
+            return Some(Ok(true));
+        };
+
+        let lower = val.trim().to_ascii_lowercase();
+        Some(match lower.as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" => Ok(true),
+            "false" | "f" | "no" | "n" | "off" | "0" | "" => Ok(false),
+            _ => Err(BoolError(val.to_string())),
+        })
+    }
+
+    fn default_value() -> Option<Self> {
+        Some(false)
+    }
+}
+
+/// A `bool` that never fails to parse: only `true`, `1`, and `t` are true,
+/// and anything else is silently false.
+///
+/// This is how `bool` used to behave; prefer plain `bool` unless you depend
+/// on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LenientBool(pub bool);
+
+impl ArgumentType for LenientBool {
     type Error = Infallible;
 
     const CONSUMES: bool = false;
 
     fn from_value(val: Option<&str>) -> ArgResult<Self> {
-        Some(Ok(if let Some(val) = val {
+        Some(Ok(Self(if let Some(val) = val {
             ["true", "1", "t"].contains(&val)
         } else {
             true
-        }))
+        })))
     }
 
     fn default_value() -> Option<Self> {
-        Some(false)
+        Some(Self(false))
+    }
+}
+
+impl From<LenientBool> for bool {
+    fn from(b: LenientBool) -> Self {
+        b.0
+    }
+}
+
+macro_rules! impl_os_strings {
+    ( $( $typ:ty => $name:literal ),+ $(,)? ) => {
+        $(
+        impl ArgumentType for $typ {
+            type Error = Infallible;
+
+            const ACCEPTS_NON_UTF8: bool = true;
+            const VALUE_NAME: &'static str = $name;
+
+            fn from_value(val: Option<&str>) -> ArgResult<Self> {
+                val.map(|val| Ok(val.into()))
+            }
+
+            fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+                val.map(|val| Ok(val.into()))
+            }
+        }
+        )+
+    };
+}
+
+impl_os_strings!(OsString => "STRING", PathBuf => "PATH");
+
+/// An argument that may legitimately be left out.
+///
+/// Unlike a bare `T`, a missing `Option<T>` resolves to `Some(Ok(None))`
+/// rather than `None`, so it's safe to use without a wrapper in `sarge!`.
+/// `T`'s own default is ignored, so `Option<bool>` makes a tri-state flag:
+/// `None` if missing, and `Some(true)` for `--flag` or `Some(false)` for
+/// `--flag=no`.
+impl<T: ArgumentType> ArgumentType for Option<T> {
+    type Error = T::Error;
+
+    const CONSUMES: bool = T::CONSUMES;
+    const REPEATABLE: bool = T::REPEATABLE;
+    const ACCEPTS_NON_UTF8: bool = T::ACCEPTS_NON_UTF8;
+    const VALUE_NAME: &'static str = T::VALUE_NAME;
+    const DELIMITER: Delimiter = T::DELIMITER;
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        Some(T::from_value(val)?.map(Some))
+    }
+
+    fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+        Some(T::from_os_value(val)?.map(Some))
+    }
+
+    fn from_os_values(vals: &[&OsStr], delimiter: Delimiter) -> ArgResult<Self> {
+        Some(T::from_os_values(vals, delimiter)?.map(Some))
+    }
+
+    fn default_value() -> Option<Self> {
+        Some(None)
     }
 }
 
@@ -113,16 +463,28 @@ impl<T: ArgumentType> ArgumentType for Vec<T> {
     type Error = T::Error;
 
     const REPEATABLE: bool = true;
+    const ACCEPTS_NON_UTF8: bool = T::ACCEPTS_NON_UTF8;
+    const VALUE_NAME: &'static str = T::VALUE_NAME;
+    const DELIMITER: Delimiter = T::DELIMITER;
 
     fn from_value(val: Option<&str>) -> ArgResult<Self> {
-        let bits = val?.split(',');
+        Self::from_os_value(val.map(OsStr::new))
+    }
+
+    fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+        Self::from_os_values(&[val?], Self::DELIMITER)
+    }
+
+    fn from_os_values(vals: &[&OsStr], delimiter: Delimiter) -> ArgResult<Self> {
         let mut values = Vec::new();
 
-        for bit in bits {
-            values.push(match T::from_value(Some(bit))? {
-                Ok(t) => t,
-                Err(e) => return Some(Err(e)),
-            });
+        for val in vals {
+            for bit in delimiter.split(val) {
+                values.push(match T::from_os_value(Some(&bit))? {
+                    Ok(t) => t,
+                    Err(e) => return Some(Err(e)),
+                });
+            }
         }
 
         Some(Ok(values))
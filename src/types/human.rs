@@ -0,0 +1,362 @@
+//! Human-friendly units: durations (`1m30s`) and byte sizes (`512MiB`).
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::Deref;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{ArgResult, ArgumentType};
+
+/// Splits a leading decimal number (`12`, `1.5`) off of `s`.
+fn split_number(s: &str) -> (&str, &str) {
+    let end = s
+        .find(|ch: char| !(ch.is_ascii_digit() || ch == '.'))
+        .unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Returns `number * scale`, where `number` is a decimal with an optional
+/// fractional part. Any fractional remainder smaller than `1` is truncated.
+///
+/// Returns `None` if the number is malformed or overflows.
+fn scale_decimal(number: &str, scale: u128) -> Option<u128> {
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+
+    let mut total = if int.is_empty() {
+        0
+    } else {
+        int.parse::<u128>().ok()?.checked_mul(scale)?
+    };
+
+    if !frac.is_empty() {
+        if !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        // Anything past 38 digits is far beyond the precision of any unit.
+        let frac = &frac[..frac.len().min(38)];
+        let denominator = 10u128.pow(u32::try_from(frac.len()).ok()?);
+        let numerator = frac.parse::<u128>().ok()?;
+        total = total.checked_add(numerator.checked_mul(scale)? / denominator)?;
+    }
+
+    Some(total)
+}
+
+/// An error from parsing a [`HumanDuration`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DurationError {
+    /// The value was empty.
+    Empty,
+    /// A component had a malformed number, e.g. the `1.2.3` in `1.2.3s`.
+    InvalidNumber(String),
+    /// A component had an unrecognized unit, e.g. the `y` in `3y`.
+    UnknownUnit(String),
+    /// A component other than the only one had no unit, e.g. the `30` in
+    /// `1m30`.
+    MissingUnit(String),
+    /// The duration was too large to represent.
+    Overflow,
+}
+
+impl Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty duration"),
+            Self::InvalidNumber(s) => write!(f, "Invalid number in duration: `{s}`"),
+            Self::UnknownUnit(s) => write!(
+                f,
+                "Unknown duration unit: `{s}` (expected one of ns, us, ms, s, m, h, d)"
+            ),
+            Self::MissingUnit(s) => write!(f, "Missing unit after `{s}` in duration"),
+            Self::Overflow => write!(f, "Duration is too large"),
+        }
+    }
+}
+
+impl Error for DurationError {}
+
+/// A [`Duration`] parsed from unit-suffixed components, such as `1m30s`,
+/// `250ms`, or `1.5h`.
+///
+/// Supported units are `ns`, `us` (or `µs`), `ms`, `s`, `m`, `h`, and `d`.
+/// A bare number (`90`) is taken as seconds. The `Display` implementation
+/// produces the same format, so it's suitable for showing defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    const UNITS: [(&'static str, u128); 8] = [
+        ("ns", 1),
+        ("us", 1_000),
+        ("µs", 1_000),
+        ("ms", 1_000_000),
+        ("s", 1_000_000_000),
+        ("m", 60 * 1_000_000_000),
+        ("h", 60 * 60 * 1_000_000_000),
+        ("d", 24 * 60 * 60 * 1_000_000_000),
+    ];
+}
+
+impl FromStr for HumanDuration {
+    type Err = DurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(DurationError::Empty);
+        }
+
+        let mut nanos: u128 = 0;
+        let mut rest = s;
+        while !rest.is_empty() {
+            let (number, tail) = split_number(rest);
+            if number.is_empty() {
+                return Err(DurationError::InvalidNumber(rest.to_string()));
+            }
+
+            let unit_end = tail
+                .find(|ch: char| ch.is_ascii_digit() || ch == '.')
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(unit_end);
+            let unit = unit.trim();
+
+            let scale = if unit.is_empty() {
+                // Only a lone number may omit its unit.
+                if rest.len() != s.len() {
+                    return Err(DurationError::MissingUnit(number.to_string()));
+                }
+                1_000_000_000
+            } else {
+                Self::UNITS
+                    .iter()
+                    .find(|(name, _)| *name == unit)
+                    .map(|(_, scale)| *scale)
+                    .ok_or_else(|| DurationError::UnknownUnit(unit.to_string()))?
+            };
+
+            let part = scale_decimal(number, scale)
+                .ok_or_else(|| DurationError::InvalidNumber(number.to_string()))?;
+            nanos = nanos.checked_add(part).ok_or(DurationError::Overflow)?;
+            rest = tail;
+        }
+
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| DurationError::Overflow)?;
+        #[allow(clippy::cast_possible_truncation)]
+        let subsec = (nanos % 1_000_000_000) as u32;
+        Ok(Self(Duration::new(secs, subsec)))
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return write!(f, "0s");
+        }
+
+        for (unit, scale) in Self::UNITS.iter().rev() {
+            if *unit == "µs" {
+                continue;
+            }
+
+            let count = nanos / scale;
+            if count > 0 {
+                write!(f, "{count}{unit}")?;
+                nanos %= scale;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(d: Duration) -> Self {
+        Self(d)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(d: HumanDuration) -> Self {
+        d.0
+    }
+}
+
+impl ArgumentType for HumanDuration {
+    type Error = DurationError;
+
+    const VALUE_NAME: &'static str = "DURATION";
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        val.map(str::parse)
+    }
+}
+
+impl ArgumentType for Duration {
+    type Error = DurationError;
+
+    const VALUE_NAME: &'static str = "DURATION";
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        Some(HumanDuration::from_value(val)?.map(Duration::from))
+    }
+}
+
+/// An error from parsing a [`ByteSize`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ByteSizeError {
+    /// The value was empty.
+    Empty,
+    /// The number was malformed, e.g. the `1.2.3` in `1.2.3MB`.
+    InvalidNumber(String),
+    /// The suffix was unrecognized, e.g. the `XB` in `3XB`.
+    UnknownUnit(String),
+    /// The size was too large to fit in a `u64`.
+    Overflow,
+}
+
+impl Display for ByteSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty byte size"),
+            Self::InvalidNumber(s) => write!(f, "Invalid number in byte size: `{s}`"),
+            Self::UnknownUnit(s) => write!(
+                f,
+                "Unknown byte size unit: `{s}` (expected e.g. B, kB, MB, KiB, MiB)"
+            ),
+            Self::Overflow => write!(f, "Byte size is too large"),
+        }
+    }
+}
+
+impl Error for ByteSizeError {}
+
+/// A number of bytes parsed from an SI (`kB`, `MB`, ...) or IEC (`KiB`,
+/// `MiB`, ...) suffixed value, such as `512MiB` or `1.5GB`.
+///
+/// Suffixes are case-insensitive, the trailing `B` is optional (`64k`,
+/// `2Gi`), and a bare number is taken as bytes. The `Display`
+/// implementation picks the largest unit that represents the value exactly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    const PREFIXES: [char; 6] = ['K', 'M', 'G', 'T', 'P', 'E'];
+
+    fn scale(unit: &str) -> Option<u128> {
+        let upper = unit.to_ascii_uppercase();
+        let unit = upper.strip_suffix('B').unwrap_or(&upper);
+        if unit.is_empty() {
+            return Some(1);
+        }
+
+        let (prefix, base) = if let Some(prefix) = unit.strip_suffix('I') {
+            (prefix, 1024u128)
+        } else {
+            (unit, 1000u128)
+        };
+
+        let mut chars = prefix.chars();
+        let (Some(prefix), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+
+        let power = Self::PREFIXES.iter().position(|&p| p == prefix)?;
+        Some(base.pow(u32::try_from(power).ok()? + 1))
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ByteSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ByteSizeError::Empty);
+        }
+
+        let (number, unit) = split_number(s);
+        if number.is_empty() {
+            return Err(ByteSizeError::InvalidNumber(s.to_string()));
+        }
+
+        let unit = unit.trim_start();
+        let scale =
+            Self::scale(unit).ok_or_else(|| ByteSizeError::UnknownUnit(unit.to_string()))?;
+        let bytes = scale_decimal(number, scale)
+            .ok_or_else(|| ByteSizeError::InvalidNumber(number.to_string()))?;
+
+        u64::try_from(bytes)
+            .map(Self)
+            .map_err(|_| ByteSizeError::Overflow)
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = u128::from(self.0);
+
+        for (base, suffix) in [(1024u128, "iB"), (1000, "B")] {
+            for (power, prefix) in Self::PREFIXES.iter().enumerate().rev() {
+                #[allow(clippy::cast_possible_truncation)]
+                let scale = base.pow(power as u32 + 1);
+                if bytes != 0 && bytes % scale == 0 {
+                    let prefix = if *prefix == 'K' && base == 1000 {
+                        'k'
+                    } else {
+                        *prefix
+                    };
+
+                    return write!(f, "{}{prefix}{suffix}", bytes / scale);
+                }
+            }
+        }
+
+        write!(f, "{bytes}B")
+    }
+}
+
+impl Deref for ByteSize {
+    type Target = u64;
+
+    fn deref(&self) -> &u64 {
+        &self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl ArgumentType for ByteSize {
+    type Error = ByteSizeError;
+
+    const VALUE_NAME: &'static str = "SIZE";
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        val.map(str::parse)
+    }
+}
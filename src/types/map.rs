@@ -0,0 +1,129 @@
+//! Key-value pairs (`-D name=value`) and maps built from them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt::{self, Debug, Display};
+use std::hash::{BuildHasher, Hash};
+
+use super::{split_once_os, ArgResult, ArgumentType, Delimiter};
+
+/// An error from parsing a `key=value` pair.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeyValueError<K, V> {
+    /// The pair had no `=`. Holds a lossy conversion of the pair.
+    MissingSeparator(String),
+    /// The key failed to parse.
+    Key(K),
+    /// The value failed to parse.
+    Value(V),
+}
+
+impl<K: Display, V: Display> Display for KeyValueError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSeparator(s) => write!(f, "Expected `key=value`, found `{s}`"),
+            Self::Key(e) => write!(f, "Invalid key: {e}"),
+            Self::Value(e) => write!(f, "Invalid value: {e}"),
+        }
+    }
+}
+
+impl<K: Debug + Display, V: Debug + Display> Error for KeyValueError<K, V> {}
+
+/// A single `key=value` pair, split on the first `=`.
+///
+/// Use `Vec<(K, V)>` to keep every pair in order, or a `HashMap`/`BTreeMap`
+/// to let later keys replace earlier ones. Each occurrence is one pair, so
+/// `-D CFLAGS=-O2,-g` keeps its comma; set a
+/// [`Full::delimiter`](crate::tag::Full::delimiter) to pass several pairs at
+/// once.
+impl<K: ArgumentType, V: ArgumentType> ArgumentType for (K, V) {
+    type Error = KeyValueError<K::Error, V::Error>;
+
+    const ACCEPTS_NON_UTF8: bool = K::ACCEPTS_NON_UTF8 && V::ACCEPTS_NON_UTF8;
+    const VALUE_NAME: &'static str = "KEY=VALUE";
+    const DELIMITER: Delimiter = Delimiter::None;
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        Self::from_os_value(val.map(OsStr::new))
+    }
+
+    fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+        let val = val?;
+        let Some((key, value)) = split_once_os(val, b'=') else {
+            return Some(Err(KeyValueError::MissingSeparator(
+                val.to_string_lossy().into_owned(),
+            )));
+        };
+
+        let key = match K::from_os_value(Some(key))? {
+            Ok(key) => key,
+            Err(e) => return Some(Err(KeyValueError::Key(e))),
+        };
+
+        let value = match V::from_os_value(Some(value))? {
+            Ok(value) => value,
+            Err(e) => return Some(Err(KeyValueError::Value(e))),
+        };
+
+        Some(Ok((key, value)))
+    }
+}
+
+impl<K, V, S> ArgumentType for HashMap<K, V, S>
+where
+    K: ArgumentType + Eq + Hash,
+    V: ArgumentType,
+    S: BuildHasher + Default,
+{
+    type Error = KeyValueError<K::Error, V::Error>;
+
+    const REPEATABLE: bool = true;
+    const ACCEPTS_NON_UTF8: bool = <(K, V)>::ACCEPTS_NON_UTF8;
+    const VALUE_NAME: &'static str = "KEY=VALUE";
+    const DELIMITER: Delimiter = Delimiter::None;
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        Self::from_os_value(val.map(OsStr::new))
+    }
+
+    fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+        Self::from_os_values(&[val?], Self::DELIMITER)
+    }
+
+    fn from_os_values(vals: &[&OsStr], delimiter: Delimiter) -> ArgResult<Self> {
+        Some(
+            Vec::<(K, V)>::from_os_values(vals, delimiter)?
+                .map(|pairs| pairs.into_iter().collect()),
+        )
+    }
+}
+
+impl<K, V> ArgumentType for BTreeMap<K, V>
+where
+    K: ArgumentType + Ord,
+    V: ArgumentType,
+{
+    type Error = KeyValueError<K::Error, V::Error>;
+
+    const REPEATABLE: bool = true;
+    const ACCEPTS_NON_UTF8: bool = <(K, V)>::ACCEPTS_NON_UTF8;
+    const VALUE_NAME: &'static str = "KEY=VALUE";
+    const DELIMITER: Delimiter = Delimiter::None;
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        Self::from_os_value(val.map(OsStr::new))
+    }
+
+    fn from_os_value(val: Option<&OsStr>) -> ArgResult<Self> {
+        Self::from_os_values(&[val?], Self::DELIMITER)
+    }
+
+    fn from_os_values(vals: &[&OsStr], delimiter: Delimiter) -> ArgResult<Self> {
+        Some(
+            Vec::<(K, V)>::from_os_values(vals, delimiter)?
+                .map(|pairs| pairs.into_iter().collect()),
+        )
+    }
+}
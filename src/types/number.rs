@@ -0,0 +1,155 @@
+//! Integers written like Rust literals (`0x7fff`, `1_000`) and ranges.
+
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::num::ParseIntError;
+use std::ops::{Deref, RangeInclusive};
+use std::str::FromStr;
+
+use super::{ArgResult, ArgumentType};
+
+mod sealed {
+    use std::num::ParseIntError;
+
+    /// The integer primitives, which all have `from_str_radix`.
+    pub trait Integer: Sized {
+        fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+    }
+
+    macro_rules! impl_integer {
+        ( $( $typ:ty ),+ $(,)? ) => {
+            $(
+            impl Integer for $typ {
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$typ>::from_str_radix(src, radix)
+                }
+            }
+            )+
+        };
+    }
+
+    impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+}
+
+/// An integer parsed like a Rust integer literal: it may have a `0x`, `0o`,
+/// or `0b` radix prefix (in either case), `_` digit separators, and a sign.
+///
+/// For example, `0x7fff_0000`, `0b1010`, `-0o17`, and `1_000_000` are all
+/// accepted. Plain integer types only accept plain decimal numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LiteralInt<T>(pub T);
+
+impl<T: sealed::Integer> FromStr for LiteralInt<T> {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Always passing an explicit sign rejects a second one after the
+        // prefix, such as `0x-5`.
+        let (sign, rest) = match s.strip_prefix('-') {
+            Some(rest) => ('-', rest),
+            None => ('+', s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let prefix = rest.get(..2).map(str::to_ascii_lowercase);
+        let (radix, digits) = match prefix.as_deref() {
+            Some("0x") => (16, &rest[2..]),
+            Some("0o") => (8, &rest[2..]),
+            Some("0b") => (2, &rest[2..]),
+            _ => (10, rest),
+        };
+
+        let mut number = String::with_capacity(digits.len() + 1);
+        number.push(sign);
+        number.extend(digits.chars().filter(|&ch| ch != '_'));
+
+        T::from_str_radix(&number, radix).map(Self)
+    }
+}
+
+impl<T: Display> Display for LiteralInt<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Deref for LiteralInt<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: sealed::Integer> ArgumentType for LiteralInt<T> {
+    type Error = ParseIntError;
+
+    const VALUE_NAME: &'static str = "N";
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        val.map(str::parse)
+    }
+}
+
+/// An error from parsing a `RangeInclusive<T>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RangeError<E> {
+    /// There was no `..`, `..=`, or `-` between the bounds. Holds the value.
+    MissingSeparator(String),
+    /// The start of the range failed to parse.
+    Start(E),
+    /// The end of the range failed to parse.
+    End(E),
+    /// The start of the range was after the end. Holds the value.
+    Reversed(String),
+}
+
+impl<E: Display> Display for RangeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSeparator(s) => {
+                write!(f, "Expected a range like `1..5` or `1-5`, found `{s}`")
+            }
+            Self::Start(e) => write!(f, "Invalid start of range: {e}"),
+            Self::End(e) => write!(f, "Invalid end of range: {e}"),
+            Self::Reversed(s) => write!(f, "Start of range is after its end: `{s}`"),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for RangeError<E> {}
+
+/// An inclusive range, written as `start..end`, `start..=end`, or
+/// `start-end`. A leading `-` is taken as the sign of `start`, so `-5--1` is
+/// the range from -5 to -1.
+impl<T: ArgumentType + PartialOrd> ArgumentType for RangeInclusive<T> {
+    type Error = RangeError<T::Error>;
+
+    const VALUE_NAME: &'static str = "RANGE";
+
+    fn from_value(val: Option<&str>) -> ArgResult<Self> {
+        let val = val?;
+        let (start, end) = if let Some((start, end)) = val.split_once("..") {
+            (start, end.strip_prefix('=').unwrap_or(end))
+        } else if let Some(i) = val.get(1..).and_then(|rest| rest.find('-')) {
+            (&val[..=i], &val[i + 2..])
+        } else {
+            return Some(Err(RangeError::MissingSeparator(val.to_string())));
+        };
+
+        let start = match T::from_value(Some(start.trim()))? {
+            Ok(start) => start,
+            Err(e) => return Some(Err(RangeError::Start(e))),
+        };
+
+        let end = match T::from_value(Some(end.trim()))? {
+            Ok(end) => end,
+            Err(e) => return Some(Err(RangeError::End(e))),
+        };
+
+        if start > end {
+            return Some(Err(RangeError::Reversed(val.to_string())));
+        }
+
+        Some(Ok(start..=end))
+    }
+}